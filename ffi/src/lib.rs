@@ -328,6 +328,8 @@ pub enum KernelError {
     InvalidStructDataError,
     InternalError,
     InvalidExpression,
+    UnsupportedDataTypeError,
+    AllocationLimitExceededError,
 }
 
 impl From<Error> for KernelError {
@@ -374,6 +376,8 @@ impl From<Error> for KernelError {
                 backtrace: _,
             } => Self::from(*source),
             Error::InvalidExpressionEvaluation(_) => KernelError::InvalidExpression,
+            Error::UnsupportedDataType { .. } => KernelError::UnsupportedDataTypeError,
+            Error::AllocationLimitExceeded { .. } => KernelError::AllocationLimitExceededError,
         }
     }
 }
@@ -0,0 +1,137 @@
+//! Benchmarks of evaluating common expressions with the arrow expression handler, over batches of
+//! a range of sizes. Run them with:
+//!
+//! ```sh
+//! cargo bench -p delta_kernel --bench expression_evaluation
+//! ```
+
+use std::sync::Arc;
+
+use arrow_array::types::Int32Type;
+use arrow_array::{
+    Array, ArrayRef, BooleanArray, DictionaryArray, Int32Array, RecordBatch, StringArray,
+};
+use arrow_schema::{DataType as ArrowDataType, Field, Schema};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use delta_kernel::engine::arrow_data::ArrowEngineData;
+use delta_kernel::engine::arrow_expression::ArrowExpressionHandler;
+use delta_kernel::expressions::Expression;
+use delta_kernel::schema::{DataType, StructField, StructType};
+use delta_kernel::ExpressionHandler;
+
+const ROW_COUNTS: [usize; 3] = [1_000, 100_000, 1_000_000];
+
+fn create_batch(num_rows: usize) -> ArrowEngineData {
+    let schema = Schema::new(vec![
+        Field::new("a", ArrowDataType::Int32, false),
+        Field::new("b", ArrowDataType::Int32, false),
+        Field::new("c", ArrowDataType::Int32, false),
+        Field::new("x", ArrowDataType::Boolean, false),
+        Field::new("y", ArrowDataType::Boolean, false),
+    ]);
+    // kept small enough that `a + b * c` can't overflow
+    let ints =
+        |factor: i32| Int32Array::from_iter_values((0..num_rows as i32).map(|i| i % 1000 * factor));
+    let bools =
+        |modulus: usize| BooleanArray::from_iter((0..num_rows).map(|i| Some(i % modulus == 0)));
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(ints(1)),
+            Arc::new(ints(3)),
+            Arc::new(ints(7)),
+            Arc::new(bools(2)),
+            Arc::new(bools(3)),
+        ],
+    )
+    .unwrap();
+    ArrowEngineData::new(batch)
+}
+
+fn input_schema() -> Arc<StructType> {
+    Arc::new(StructType::new(vec![
+        StructField::new("a", DataType::INTEGER, false),
+        StructField::new("b", DataType::INTEGER, false),
+        StructField::new("c", DataType::INTEGER, false),
+        StructField::new("x", DataType::BOOLEAN, false),
+        StructField::new("y", DataType::BOOLEAN, false),
+    ]))
+}
+
+fn expression_evaluation(c: &mut Criterion) {
+    let cases = [
+        (
+            "a > k",
+            Expression::column("a").gt(Expression::literal(500)),
+            DataType::BOOLEAN,
+        ),
+        (
+            "x AND y",
+            Expression::column("x").and(Expression::column("y")),
+            DataType::BOOLEAN,
+        ),
+        (
+            "a + b * c",
+            Expression::column("a") + Expression::column("b") * Expression::column("c"),
+            DataType::INTEGER,
+        ),
+    ];
+    let handler = ArrowExpressionHandler::default();
+    for (name, expression, output_type) in cases {
+        let evaluator = handler.get_evaluator(input_schema(), expression, output_type);
+        let mut group = c.benchmark_group(name);
+        for num_rows in ROW_COUNTS {
+            let batch = create_batch(num_rows);
+            group.throughput(Throughput::Elements(num_rows as u64));
+            group.bench_with_input(BenchmarkId::from_parameter(num_rows), &batch, |b, batch| {
+                b.iter(|| evaluator.evaluate(batch).unwrap())
+            });
+        }
+        group.finish();
+    }
+}
+
+/// A string column with only a few distinct values, either plain or dictionary-encoded.
+fn create_low_cardinality_batch(num_rows: usize, dictionary_encoded: bool) -> ArrowEngineData {
+    let strings: Vec<_> = (0..num_rows).map(|i| format!("value-{}", i % 10)).collect();
+    let (column, data_type): (ArrayRef, _) = if dictionary_encoded {
+        let dictionary: DictionaryArray<Int32Type> = strings.iter().map(String::as_str).collect();
+        let data_type = dictionary.data_type().clone();
+        (Arc::new(dictionary), data_type)
+    } else {
+        (Arc::new(StringArray::from(strings)), ArrowDataType::Utf8)
+    };
+    let schema = Schema::new(vec![Field::new("s", data_type, false)]);
+    ArrowEngineData::new(RecordBatch::try_new(Arc::new(schema), vec![column]).unwrap())
+}
+
+fn like_on_low_cardinality_column(c: &mut Criterion) {
+    let input_schema = Arc::new(StructType::new(vec![StructField::new(
+        "s",
+        DataType::STRING,
+        false,
+    )]));
+    let expression = Expression::column("s").like(Expression::literal("%ue-1%"));
+    let handler = ArrowExpressionHandler::default();
+    let evaluator = handler.get_evaluator(input_schema, expression, DataType::BOOLEAN);
+    for (name, dictionary_encoded) in [("s LIKE p (plain)", false), ("s LIKE p (dictionary)", true)]
+    {
+        let mut group = c.benchmark_group(name);
+        for num_rows in ROW_COUNTS {
+            let batch = create_low_cardinality_batch(num_rows, dictionary_encoded);
+            group.throughput(Throughput::Elements(num_rows as u64));
+            group.bench_with_input(BenchmarkId::from_parameter(num_rows), &batch, |b, batch| {
+                b.iter(|| evaluator.evaluate(batch).unwrap())
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(
+    benches,
+    expression_evaluation,
+    like_on_low_cardinality_column
+);
+criterion_main!(benches);
@@ -102,8 +102,11 @@ impl TryFrom<&DataType> for ArrowDataType {
                     PrimitiveType::Boolean => Ok(ArrowDataType::Boolean),
                     PrimitiveType::Binary => Ok(ArrowDataType::Binary),
                     PrimitiveType::Decimal(precision, scale) => {
-                        PrimitiveType::check_decimal(*precision, *scale)
-                            .map_err(|e| ArrowError::from_external_error(e.into()))?;
+                        PrimitiveType::check_decimal(*precision, *scale).map_err(|_| {
+                            ArrowError::from_external_error(
+                                Error::unsupported_data_type(t.clone()).into(),
+                            )
+                        })?;
                         Ok(ArrowDataType::Decimal128(*precision, *scale as i8))
                     }
                     PrimitiveType::Date => {
@@ -1,31 +1,42 @@
 //! Expression handling based on arrow-rs compute kernels.
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
-use arrow_arith::boolean::{and_kleene, is_null, not, or_kleene};
+use arrow_arith::boolean::{and, and_kleene, is_not_null, is_null, not, or_kleene};
 use arrow_arith::numeric::{add, div, mul, sub};
 use arrow_array::cast::AsArray;
 use arrow_array::types::*;
 use arrow_array::{
-    Array, ArrayRef, BinaryArray, BooleanArray, Date32Array, Datum, Decimal128Array, Float32Array,
-    Float64Array, Int16Array, Int32Array, Int64Array, Int8Array, ListArray, RecordBatch,
-    StringArray, StructArray, TimestampMicrosecondArray,
+    make_array, new_empty_array, new_null_array, Array, ArrayRef, BinaryArray, BooleanArray,
+    Date32Array, Datum, Decimal128Array, Float32Array, Float64Array, Int16Array, Int32Array,
+    Int64Array, Int8Array, LargeListArray, ListArray, RecordBatch, RecordBatchOptions, StringArray,
+    StructArray, TimestampMicrosecondArray, UInt64Array,
 };
-use arrow_buffer::OffsetBuffer;
+use arrow_buffer::{BooleanBuffer, BooleanBufferBuilder, NullBuffer, OffsetBuffer};
+use arrow_cast::{cast, cast_with_options, CastOptions};
 use arrow_ord::cmp::{distinct, eq, gt, gt_eq, lt, lt_eq, neq};
 use arrow_ord::comparison::in_list_utf8;
 use arrow_schema::{
-    ArrowError, DataType as ArrowDataType, Field as ArrowField, Fields, IntervalUnit,
-    Schema as ArrowSchema, TimeUnit,
+    ArrowError, DataType as ArrowDataType, Field as ArrowField, FieldRef, Fields, IntervalUnit,
+    Schema as ArrowSchema, TimeUnit, DECIMAL128_MAX_PRECISION,
 };
 use arrow_select::concat::concat;
-use itertools::Itertools;
+use arrow_select::filter::{filter_record_batch, prep_null_mask_filter};
+use arrow_select::interleave::interleave;
+use arrow_select::take::take;
+use itertools::{Either, Itertools};
+use roaring::RoaringTreemap;
 
 use super::arrow_conversion::LIST_ARRAY_ROOT;
+use super::arrow_functions::{evaluate_function, LikeCache};
 use crate::engine::arrow_data::ArrowEngineData;
 use crate::engine::arrow_utils::ensure_data_types;
 use crate::engine::arrow_utils::prim_array_cmp;
 use crate::error::{DeltaResult, Error};
-use crate::expressions::{BinaryOperator, Expression, Scalar, UnaryOperator, VariadicOperator};
+use crate::expressions::{
+    BinaryOperator, Expression, Scalar, ScalarFunction, UnaryOperator, VariadicOperator,
+};
 use crate::schema::{DataType, PrimitiveType, SchemaRef};
 use crate::{EngineData, ExpressionEvaluator, ExpressionHandler};
 
@@ -75,18 +86,16 @@ impl Scalar {
             }
             Array(data) => {
                 #[allow(deprecated)]
-                let values = data.array_elements();
-                let vecs: Vec<_> = values.iter().map(|v| v.to_array(num_rows)).try_collect()?;
-                let values: Vec<_> = vecs.iter().map(|x| x.as_ref()).collect();
-                let offsets: Vec<_> = vecs.iter().map(|v| v.len()).collect();
-                let offset_buffer = OffsetBuffer::from_lengths(offsets);
-                let field = ArrowField::try_from(data.array_type())?;
-                Arc::new(ListArray::new(
-                    Arc::new(field),
-                    offset_buffer,
-                    concat(values.as_slice())?,
-                    None,
-                ))
+                let elements = data.array_elements();
+                let field = Arc::new(ArrowField::try_from(data.array_type())?);
+                let element_arrays: Vec<_> =
+                    elements.iter().map(|e| e.to_array(1)).try_collect()?;
+                let element_arrays: Vec<_> = element_arrays.iter().map(|a| a.as_ref()).collect();
+                let elements = match element_arrays.is_empty() {
+                    true => new_empty_array(field.data_type()),
+                    false => concat(&element_arrays)?,
+                };
+                repeat_list(field, &elements, num_rows)?
             }
             Null(data_type) => match data_type {
                 DataType::Primitive(primitive) => match primitive {
@@ -120,55 +129,160 @@ impl Scalar {
                         ArrowField::new(LIST_ARRAY_ROOT, t.element_type().try_into()?, true);
                     Arc::new(ListArray::new_null(Arc::new(field), num_rows))
                 }
-                DataType::Map { .. } => unimplemented!(),
+                DataType::Map { .. } => {
+                    return Err(Error::unsupported_data_type(data_type.clone()))
+                }
             },
         };
         Ok(arr)
     }
 }
 
+impl BinaryOperator {
+    /// Whether this operator compares its operands, i.e. produces a boolean from two values of the
+    /// same type.
+    pub(crate) fn is_comparison(&self) -> bool {
+        use BinaryOperator::*;
+        matches!(
+            self,
+            LessThan
+                | LessThanOrEqual
+                | GreaterThan
+                | GreaterThanOrEqual
+                | Equal
+                | NotEqual
+                | Distinct
+        )
+    }
+}
+
+/// Whether a list array of `num_rows` lists of `list_len` elements each has more elements in total
+/// than 32-bit offsets can address, and so must be a `LargeList`.
+fn needs_large_list_offsets(list_len: usize, num_rows: usize) -> bool {
+    !matches!(list_len.checked_mul(num_rows), Some(total) if total <= i32::MAX as usize)
+}
+
+/// A list array of `num_rows` rows that each hold the list `elements`. This is a `List`, unless
+/// the elements of all its rows together are too many for 32-bit offsets, in which case it is a
+/// `LargeList` instead.
+fn repeat_list(field: FieldRef, elements: &ArrayRef, num_rows: usize) -> DeltaResult<ArrayRef> {
+    let list_len = elements.len();
+    let indices = UInt64Array::from_iter_values((0..num_rows).flat_map(|_| 0..list_len as u64));
+    let values = take(elements, &indices, None)?;
+    let lengths = std::iter::repeat_n(list_len, num_rows);
+    let list: ArrayRef = if needs_large_list_offsets(list_len, num_rows) {
+        let offsets = OffsetBuffer::<i64>::from_lengths(lengths);
+        Arc::new(LargeListArray::try_new(field, offsets, values, None)?)
+    } else {
+        let offsets = OffsetBuffer::<i32>::from_lengths(lengths);
+        Arc::new(ListArray::try_new(field, offsets, values, None)?)
+    };
+    Ok(list)
+}
+
+/// Replaces the nulls of a boolean array with false, e.g. to use it as a filter. Unlike arrow's
+/// `prep_null_mask_filter`, this also accepts an array without a null buffer.
+fn null_as_false(arr: &BooleanArray) -> BooleanArray {
+    match arr.nulls() {
+        Some(_) => prep_null_mask_filter(arr),
+        None => arr.clone(),
+    }
+}
+
 fn wrap_comparison_result(arr: BooleanArray) -> ArrayRef {
     Arc::new(arr) as Arc<dyn Array>
 }
 
 trait ProvidesColumnByName {
-    fn column_by_name(&self, name: &str) -> Option<&Arc<dyn Array>>;
+    fn fields(&self) -> &Fields;
+    fn column(&self, i: usize) -> &Arc<dyn Array>;
 }
 
 impl ProvidesColumnByName for RecordBatch {
-    fn column_by_name(&self, name: &str) -> Option<&Arc<dyn Array>> {
-        self.column_by_name(name)
+    fn fields(&self) -> &Fields {
+        self.schema_ref().fields()
+    }
+    fn column(&self, i: usize) -> &Arc<dyn Array> {
+        self.column(i)
     }
 }
 
 impl ProvidesColumnByName for StructArray {
-    fn column_by_name(&self, name: &str) -> Option<&Arc<dyn Array>> {
-        self.column_by_name(name)
+    fn fields(&self) -> &Fields {
+        self.fields()
+    }
+    fn column(&self, i: usize) -> &Arc<dyn Array> {
+        self.column(i)
     }
 }
 
-fn extract_column<'array, 'path>(
+/// Look up a direct child of `array` by name, ignoring case if the options ask for it. A name that
+/// matches more than one child (e.g. a batch with duplicate column names after a join) is
+/// ambiguous and thus an error, rather than silently resolving to the first match.
+fn find_column<'array>(
     array: &'array dyn ProvidesColumnByName,
+    name: &str,
+    options: &EvaluationOptions,
+) -> Result<Option<&'array Arc<dyn Array>>, ArrowError> {
+    let lowercase_name = name.to_lowercase();
+    let mut matches = array.fields().iter().positions(|field| {
+        if options.case_insensitive_columns {
+            field.name().to_lowercase() == lowercase_name
+        } else {
+            field.name() == name
+        }
+    });
+    match (matches.next(), matches.next()) {
+        (None, _) => Ok(None),
+        (Some(i), None) => Ok(Some(array.column(i))),
+        (Some(_), Some(_)) if options.case_insensitive_columns => {
+            Err(ArrowError::SchemaError(format!(
+                "Ambiguous reference to field {name}, which matches several fields ignoring case",
+            )))
+        }
+        (Some(_), Some(_)) => Err(ArrowError::SchemaError(format!(
+            "Ambiguous reference to field {name}, which matches several fields",
+        ))),
+    }
+}
+
+fn extract_column<'path>(
+    array: &dyn ProvidesColumnByName,
     path_step: &str,
     remaining_path_steps: &mut impl Iterator<Item = &'path str>,
-) -> Result<&'array Arc<dyn Array>, ArrowError> {
-    let child = array
-        .column_by_name(path_step)
-        .ok_or(ArrowError::SchemaError(format!(
-            "No such field: {}",
-            path_step,
-        )))?;
+    options: &EvaluationOptions,
+) -> Result<ArrayRef, ArrowError> {
+    let child = find_column(array, path_step, options)?.ok_or(ArrowError::SchemaError(format!(
+        "No such field: {}",
+        path_step,
+    )))?;
     if let Some(next_path_step) = remaining_path_steps.next() {
         // This is not the last path step. Drill deeper.
-        extract_column(
-            column_as_struct(path_step, &Some(child))?,
-            next_path_step,
-            remaining_path_steps,
-        )
+        let struct_array = column_as_struct(path_step, &Some(child))?;
+        let nested = extract_column(struct_array, next_path_step, remaining_path_steps, options)?;
+        propagate_nulls(nested, struct_array.nulls())
     } else {
         // Last path step. Return it.
-        Ok(child)
+        Ok(child.clone())
+    }
+}
+
+/// Marks the rows of `array` that are null in its parent struct as null, since arrow does not
+/// require a struct's children to be null wherever the struct itself is.
+fn propagate_nulls(
+    array: ArrayRef,
+    parent_nulls: Option<&NullBuffer>,
+) -> Result<ArrayRef, ArrowError> {
+    let Some(parent_nulls) = parent_nulls.filter(|nulls| nulls.null_count() > 0) else {
+        return Ok(array);
+    };
+    // A null array is already null in every row, and can't have a null buffer
+    if *array.data_type() == ArrowDataType::Null {
+        return Ok(array);
     }
+    let nulls = NullBuffer::union(Some(parent_nulls), array.logical_nulls().as_ref());
+    let data = array.to_data().into_builder().nulls(nulls).build()?;
+    Ok(make_array(data))
 }
 
 fn column_as_struct<'a>(
@@ -182,34 +296,304 @@ fn column_as_struct<'a>(
         .ok_or(ArrowError::SchemaError(format!("{} is not a struct", name)))
 }
 
-fn evaluate_expression(
+/// Resolve a column reference against `batch`. A `.` in the name separates the steps of a path to a
+/// nested column, unless the batch has a top-level column whose name matches the full reference.
+fn resolve_column(
+    batch: &RecordBatch,
+    name: &str,
+    options: &EvaluationOptions,
+) -> DeltaResult<ArrayRef> {
+    if let Some(column) = find_column(batch, name, options)? {
+        return Ok(column.clone());
+    }
+    let name = strip_table_qualifier(batch, name, options)?;
+    if let Some(column) = find_column(batch, name, options)? {
+        return Ok(column.clone());
+    }
+    // TODO properly handle nested columns
+    // https://github.com/delta-incubator/delta-kernel-rs/issues/86
+    if name.contains('.') {
+        let mut path = name.split('.');
+        // Safety: we know that the first path step exists, because we checked for '.'
+        Ok(extract_column(
+            batch,
+            path.next().unwrap(),
+            &mut path,
+            options,
+        )?)
+    } else {
+        Err(Error::missing_column(name))
+    }
+}
+
+fn strip_table_qualifier<'a>(
+    batch: &RecordBatch,
+    name: &'a str,
+    options: &EvaluationOptions,
+) -> DeltaResult<&'a str> {
+    let Some(qualifier) = &options.table_qualifier else {
+        return Ok(name);
+    };
+    match name.strip_prefix(qualifier.as_str()) {
+        Some(rest)
+            if rest.starts_with('.') && find_column(batch, qualifier, options)?.is_none() =>
+        {
+            Ok(&rest[1..])
+        }
+        _ => Ok(name),
+    }
+}
+
+/// Coerces the operands of a binary operation to a common type, for combinations of types that
+/// kernel accepts but arrow's kernels do not support directly:
+///
+/// - A decimal combined with a float: both are converted to `Float64`. Note that this is lossy, as
+///   a decimal generally has no exact floating point representation, and a decimal with more than
+///   15 significant digits may not even round-trip.
+/// - An integer combined with a decimal: the integer is promoted to a decimal with the scale of
+///   the other operand and enough precision to hold any value of the integer type, so the result
+///   is a decimal, as in SQL.
+/// - An integer combined with a float: both are converted to `Float64`, which holds any `Int32`
+///   exactly (but not every `Int64`).
+///
+/// Two integers are left as they are, so dividing them is integer division, as in arrow: `a / 2`
+/// is an integer, and only `a / 2.0` is a float.
+fn coerce_operands(left: ArrayRef, right: ArrayRef) -> DeltaResult<(ArrayRef, ArrayRef)> {
+    use ArrowDataType::*;
+    // TODO the arrow version we build against has no `Decimal32` or `Decimal64`. When we upgrade
+    // to one that does, promote those to `Decimal128` here, so that they combine with the decimals
+    // kernel produces (which are always `Decimal128`).
+    match (left.data_type(), right.data_type()) {
+        (Decimal128(..), Float32 | Float64)
+        | (Float32 | Float64, Decimal128(..))
+        | (Int8 | Int16 | Int32 | Int64, Float32 | Float64)
+        | (Float32 | Float64, Int8 | Int16 | Int32 | Int64) => {
+            Ok((cast(&left, &Float64)?, cast(&right, &Float64)?))
+        }
+        (Int8 | Int16 | Int32 | Int64, Decimal128(_, scale)) => {
+            let left = promote_to_decimal(&left, *scale)?;
+            Ok((left, right))
+        }
+        (Decimal128(_, scale), Int8 | Int16 | Int32 | Int64) => {
+            let right = promote_to_decimal(&right, *scale)?;
+            Ok((left, right))
+        }
+        _ => Ok((left, right)),
+    }
+}
+
+/// Arrow only compares decimals of exactly the same precision and scale, so decimals of differing
+/// types are both cast to a type that can represent any value of either. That is only needed for
+/// comparisons: arrow's arithmetic kernels already rescale their decimal operands.
+fn unify_decimal_types(left: ArrayRef, right: ArrayRef) -> DeltaResult<(ArrayRef, ArrayRef)> {
+    match (left.data_type(), right.data_type()) {
+        (ArrowDataType::Decimal128(p1, s1), ArrowDataType::Decimal128(p2, s2))
+            if (p1, s1) != (p2, s2) =>
+        {
+            let scale = *s1.max(s2);
+            let integer_digits = (*p1 as i8 - s1).max(*p2 as i8 - s2).max(0) as u8;
+            let precision = (integer_digits + scale.max(0) as u8).min(DECIMAL128_MAX_PRECISION);
+            let to_type = ArrowDataType::Decimal128(precision, scale);
+            let options = CastOptions {
+                safe: false,
+                ..Default::default()
+            };
+            Ok((
+                cast_with_options(&left, &to_type, &options)?,
+                cast_with_options(&right, &to_type, &options)?,
+            ))
+        }
+        _ => Ok((left, right)),
+    }
+}
+
+fn promote_to_decimal(arr: &ArrayRef, scale: i8) -> DeltaResult<ArrayRef> {
+    let digits = match arr.data_type() {
+        ArrowDataType::Int8 => 3,
+        ArrowDataType::Int16 => 5,
+        ArrowDataType::Int32 => 10,
+        _ => 19,
+    };
+    let precision = (digits + scale.max(0) as u8).min(DECIMAL128_MAX_PRECISION);
+    let options = CastOptions {
+        safe: false,
+        ..Default::default()
+    };
+    let to_type = ArrowDataType::Decimal128(precision, scale);
+    Ok(cast_with_options(arr, &to_type, &options)?)
+}
+
+/// Normalizes the strings of a string array to NFC. Arrays of any other type are returned
+/// unchanged.
+#[cfg(feature = "unicode-functions")]
+fn normalize_unicode(arr: ArrayRef) -> ArrayRef {
+    use unicode_normalization::UnicodeNormalization;
+    match arr.as_string_opt::<i32>() {
+        Some(strings) => {
+            let normalized: StringArray = strings
+                .iter()
+                .map(|s| s.map(|s| s.nfc().collect::<String>()))
+                .collect();
+            Arc::new(normalized)
+        }
+        None => arr,
+    }
+}
+
+/// Replaces any `-0.0` in a floating point array with `+0.0`, so that arrow's comparison kernels
+/// (which order floats by IEEE 754 totalOrder) consider the two zeros equal. Arrays without a
+/// `-0.0`, and arrays of any other type, are returned unchanged rather than copied.
+fn normalize_float_zeros(arr: ArrayRef) -> ArrayRef {
+    match arr.data_type() {
+        ArrowDataType::Float32 => {
+            let floats = arr.as_primitive::<Float32Type>();
+            if !floats
+                .values()
+                .iter()
+                .any(|v| *v == 0.0 && v.is_sign_negative())
+            {
+                return arr;
+            }
+            Arc::new(floats.unary::<_, Float32Type>(|v| if v == 0.0 { 0.0 } else { v }))
+        }
+        ArrowDataType::Float64 => {
+            let floats = arr.as_primitive::<Float64Type>();
+            if !floats
+                .values()
+                .iter()
+                .any(|v| *v == 0.0 && v.is_sign_negative())
+            {
+                return arr;
+            }
+            Arc::new(floats.unary::<_, Float64Type>(|v| if v == 0.0 { 0.0 } else { v }))
+        }
+        _ => arr,
+    }
+}
+
+/// The state of a single evaluation of an expression over a batch.
+struct EvaluationContext<'a> {
+    options: &'a EvaluationOptions,
+    /// If present, rows that fail to evaluate are marked here (and evaluate to null) instead of
+    /// failing the whole evaluation.
+    row_errors: Option<RefCell<BooleanBuffer>>,
+    /// Constant values of columns that are not in the batch, like the partition columns of a file.
+    partition_values: Option<&'a HashMap<String, Scalar>>,
+    /// The total size of the intermediate results computed so far, in bytes.
+    allocated: Cell<usize>,
+    /// The matches of LIKE patterns against dictionaries, kept across evaluations.
+    like_cache: Option<&'a LikeCache>,
+}
+
+impl<'a> EvaluationContext<'a> {
+    fn new(options: &'a EvaluationOptions) -> Self {
+        Self {
+            options,
+            row_errors: None,
+            partition_values: None,
+            allocated: Cell::new(0),
+            like_cache: None,
+        }
+    }
+
+    /// A context for evaluating over an empty batch, just to check that an expression is valid,
+    /// which doesn't capture row errors or count towards the allocation limit.
+    fn for_type_check(&self) -> Self {
+        Self {
+            options: self.options,
+            row_errors: None,
+            partition_values: self.partition_values,
+            allocated: Cell::new(0),
+            like_cache: None,
+        }
+    }
+
+    fn with_like_cache(mut self, like_cache: &'a LikeCache) -> Self {
+        self.like_cache = Some(like_cache);
+        self
+    }
+
+    /// Account for a newly computed intermediate result, failing if that takes the evaluation over
+    /// its allocation limit.
+    fn track_allocation(&self, result: &ArrayRef) -> DeltaResult<()> {
+        let Some(limit) = self.options.allocation_limit else {
+            return Ok(());
+        };
+        let allocated = self.allocated.get() + result.get_array_memory_size();
+        self.allocated.set(allocated);
+        if allocated > limit {
+            return Err(Error::allocation_limit_exceeded(limit));
+        }
+        Ok(())
+    }
+
+    fn with_partition_values(mut self, partition_values: &'a HashMap<String, Scalar>) -> Self {
+        self.partition_values = Some(partition_values);
+        self
+    }
+
+    fn with_row_errors(mut self, num_rows: usize) -> Self {
+        self.row_errors = Some(RefCell::new(BooleanBuffer::new_unset(num_rows)));
+        self
+    }
+}
+
+pub(crate) fn evaluate_expression(
+    expression: &Expression,
+    batch: &RecordBatch,
+    result_type: Option<&DataType>,
+) -> DeltaResult<ArrayRef> {
+    evaluate_expression_with_options(
+        expression,
+        batch,
+        result_type,
+        &EvaluationOptions::default(),
+    )
+}
+
+pub(crate) fn evaluate_expression_with_options(
+    expression: &Expression,
+    batch: &RecordBatch,
+    result_type: Option<&DataType>,
+    options: &EvaluationOptions,
+) -> DeltaResult<ArrayRef> {
+    let ctx = EvaluationContext::new(options);
+    evaluate_expression_with_context(expression, batch, result_type, &ctx)
+}
+
+fn evaluate_expression_with_context(
+    expression: &Expression,
+    batch: &RecordBatch,
+    result_type: Option<&DataType>,
+    ctx: &EvaluationContext<'_>,
+) -> DeltaResult<ArrayRef> {
+    let result = evaluate_expression_inner(expression, batch, result_type, ctx)?;
+    // A column reference doesn't allocate, it just shares the batch's column, and an alias just
+    // names the result of its expression
+    if !matches!(expression, Expression::Column(_) | Expression::Alias { .. }) {
+        ctx.track_allocation(&result)?;
+    }
+    Ok(result)
+}
+
+fn evaluate_expression_inner(
     expression: &Expression,
     batch: &RecordBatch,
     result_type: Option<&DataType>,
+    ctx: &EvaluationContext<'_>,
 ) -> DeltaResult<ArrayRef> {
     use BinaryOperator::*;
     use Expression::*;
     match (expression, result_type) {
         (Literal(scalar), _) => Ok(scalar.to_array(batch.num_rows())?),
-        (Column(name), _) => {
-            // TODO properly handle nested columns
-            // https://github.com/delta-incubator/delta-kernel-rs/issues/86
-            if name.contains('.') {
-                let mut path = name.split('.');
-                // Safety: we know that the first path step exists, because we checked for '.'
-                Ok(extract_column(batch, path.next().unwrap(), &mut path).cloned()?)
-            } else {
-                batch
-                    .column_by_name(name)
-                    .ok_or(Error::missing_column(name))
-                    .cloned()
-            }
-        }
+        (Column(name), _) => match ctx.partition_values.and_then(|values| values.get(name)) {
+            Some(value) => value.to_array(batch.num_rows()),
+            None => resolve_column(batch, name, ctx.options),
+        },
         (Struct(fields), Some(DataType::Struct(schema))) => {
-            let columns = fields
-                .iter()
-                .zip(schema.fields())
-                .map(|(expr, field)| evaluate_expression(expr, batch, Some(field.data_type())));
+            let columns = fields.iter().zip(schema.fields()).map(|(expr, field)| {
+                evaluate_expression_with_context(expr, batch, Some(field.data_type()), ctx)
+            });
             let output_cols: Vec<Arc<dyn Array>> = columns.try_collect()?;
             let output_fields: Vec<ArrowField> = output_cols
                 .iter()
@@ -230,7 +614,7 @@ fn evaluate_expression(
             "Data type is required to evaluate struct expressions",
         )),
         (UnaryOperation { op, expr }, _) => {
-            let arr = evaluate_expression(expr.as_ref(), batch, None)?;
+            let arr = evaluate_expression_with_context(expr.as_ref(), batch, None, ctx)?;
             Ok(match op {
                 UnaryOperator::Not => Arc::new(not(downcast_to_bool(&arr)?)?),
                 UnaryOperator::IsNull => Arc::new(is_null(&arr)?),
@@ -245,17 +629,16 @@ fn evaluate_expression(
             _,
         ) => match (left.as_ref(), right.as_ref()) {
             (Literal(_), Column(c)) => {
-                let list_type = batch.column_by_name(c).map(|c| c.data_type());
+                let right_arr = evaluate_expression_with_context(right.as_ref(), batch, None, ctx)?;
                 if !matches!(
-                    list_type,
-                    Some(ArrowDataType::List(_)) | Some(ArrowDataType::FixedSizeList(_, _))
+                    right_arr.data_type(),
+                    ArrowDataType::List(_) | ArrowDataType::FixedSizeList(_, _)
                 ) {
                     return Err(Error::InvalidExpressionEvaluation(format!(
                         "Right side column: {c} is not a list or a fixed size list"
                     )));
                 }
-                let left_arr = evaluate_expression(left.as_ref(), batch, None)?;
-                let right_arr = evaluate_expression(right.as_ref(), batch, None)?;
+                let left_arr = evaluate_expression_with_context(left.as_ref(), batch, None, ctx)?;
                 if let Some(string_arr) = left_arr.as_string_opt::<i32>() {
                     return in_list_utf8(string_arr, right_arr.as_list::<i32>())
                         .map(wrap_comparison_result)
@@ -313,14 +696,41 @@ fn evaluate_expression(
             _,
         ) => {
             let reverse_op = Expression::binary(In, *left.clone(), *right.clone());
-            let reverse_expr = evaluate_expression(&reverse_op, batch, None)?;
+            let reverse_expr = evaluate_expression_with_context(&reverse_op, batch, None, ctx)?;
             not(reverse_expr.as_boolean())
                 .map(wrap_comparison_result)
                 .map_err(Error::generic_err)
         }
         (BinaryOperation { op, left, right }, _) => {
-            let left_arr = evaluate_expression(left.as_ref(), batch, None)?;
-            let right_arr = evaluate_expression(right.as_ref(), batch, None)?;
+            let left_arr = evaluate_expression_with_context(left.as_ref(), batch, None, ctx)?;
+            let right_arr = evaluate_expression_with_context(right.as_ref(), batch, None, ctx)?;
+
+            // Like in SQL, comparing anything with NULL (as in the common mistake `a = NULL`
+            // instead of `a IS NULL`) is null, whatever the type of the NULL
+            let is_null_comparison = op.is_comparison()
+                && *op != Distinct
+                && [left, right]
+                    .iter()
+                    .any(|side| matches!(side.as_ref(), Literal(Scalar::Null(_))));
+            if is_null_comparison {
+                return Ok(new_null_array(&ArrowDataType::Boolean, batch.num_rows()));
+            }
+
+            let (mut left_arr, mut right_arr) = coerce_operands(left_arr, right_arr)?;
+
+            let is_comparison = op.is_comparison();
+            if is_comparison {
+                (left_arr, right_arr) = unify_decimal_types(left_arr, right_arr)?;
+            }
+            if is_comparison && !ctx.options.bitwise_float_comparison {
+                left_arr = normalize_float_zeros(left_arr);
+                right_arr = normalize_float_zeros(right_arr);
+            }
+            #[cfg(feature = "unicode-functions")]
+            if is_comparison && ctx.options.unicode_normalization {
+                left_arr = normalize_unicode(left_arr);
+                right_arr = normalize_unicode(right_arr);
+            }
 
             type Operation = fn(&dyn Datum, &dyn Datum) -> Result<Arc<dyn Array>, ArrowError>;
             let eval: Operation = match op {
@@ -338,7 +748,57 @@ fn evaluate_expression(
                 _ => return Err(Error::generic("Invalid expression given")),
             };
 
-            eval(&left_arr, &right_arr).map_err(Error::generic_err)
+            eval(&left_arr, &right_arr).map_err(|err| match err {
+                // arrow rejects combinations of operand types that it has no kernel for, like the
+                // duration produced by subtracting timestamps compared with an integer
+                ArrowError::InvalidArgumentError(_) => Error::invalid_expression(format!(
+                    "Unsupported operation {op} on {} and {}: {err}",
+                    left_arr.data_type(),
+                    right_arr.data_type(),
+                )),
+                err => Error::generic_err(err),
+            })
+        }
+        (Alias { expr, .. }, _) => evaluate_expression_with_context(expr, batch, result_type, ctx),
+        (Cast { expr, data_type }, _) => {
+            let arr = evaluate_expression_with_context(expr, batch, None, ctx)?;
+            let to_type = ArrowDataType::try_from(data_type)?;
+            let Some(row_errors) = &ctx.row_errors else {
+                let options = CastOptions {
+                    safe: false,
+                    ..Default::default()
+                };
+                return Ok(cast_with_options(&arr, &to_type, &options)?);
+            };
+            // A safe cast turns the values it cannot cast into nulls, so the rows that failed are
+            // exactly those that are null in the result but not in the input.
+            let result = cast(&arr, &to_type)?;
+            let failed = and(&is_not_null(&arr)?, &is_null(&result)?)?;
+            row_errors.replace_with(|errors| &*errors | failed.values());
+            Ok(result)
+        }
+        (
+            Case {
+                branches,
+                otherwise,
+            },
+            _,
+        ) => evaluate_case(branches, otherwise.as_deref(), batch, result_type, ctx),
+        (Function { func, args }, _) => {
+            if let (
+                ScalarFunction::Like,
+                Some(cache),
+                [strings, Literal(Scalar::String(pattern))],
+            ) = (func, ctx.like_cache, args.as_slice())
+            {
+                let strings = evaluate_expression_with_context(strings, batch, None, ctx)?;
+                return cache.like(&strings, pattern);
+            }
+            let args: Vec<_> = args
+                .iter()
+                .map(|arg| evaluate_expression_with_context(arg, batch, None, ctx))
+                .try_collect()?;
+            evaluate_function(func, &args)
         }
         (VariadicOperation { op, exprs }, None | Some(&DataType::BOOLEAN)) => {
             type Operation = fn(&BooleanArray, &BooleanArray) -> Result<BooleanArray, ArrowError>;
@@ -346,15 +806,45 @@ fn evaluate_expression(
                 VariadicOperator::And => (and_kleene, true),
                 VariadicOperator::Or => (or_kleene, false),
             };
+            // A boolean literal either decides the result on its own (`x AND false`, `x OR true`)
+            // or has no effect on it (`x AND true`, `x OR false`), so we never need to materialize
+            // it as an array and reduce over it.
+            let is_literal = |expr: &Expression, value: bool| match expr {
+                Literal(Scalar::Boolean(v)) => *v == value,
+                _ => false,
+            };
+            if exprs.iter().any(|expr| is_literal(expr, !default)) {
+                // The other operands can't change the result, but must still be valid, so resolve
+                // and type check them by evaluating them over no rows.
+                let empty_batch = batch.slice(0, 0);
+                let check_ctx = ctx.for_type_check();
+                for expr in exprs {
+                    let arr = evaluate_expression_with_context(
+                        expr,
+                        &empty_batch,
+                        result_type,
+                        &check_ctx,
+                    )?;
+                    downcast_to_bool(&arr)?;
+                }
+                let result = BooleanArray::from(vec![!default; batch.num_rows()]);
+                return Ok(wrap_comparison_result(result));
+            }
             exprs
                 .iter()
-                .map(|expr| evaluate_expression(expr, batch, result_type))
+                .filter(|expr| !is_literal(expr, default))
+                .map(|expr| evaluate_expression_with_context(expr, batch, result_type, ctx))
                 .reduce(|l, r| {
                     Ok(reducer(downcast_to_bool(&l?)?, downcast_to_bool(&r?)?)
                         .map(wrap_comparison_result)?)
                 })
                 .unwrap_or_else(|| {
-                    evaluate_expression(&Expression::literal(default), batch, result_type)
+                    evaluate_expression_with_context(
+                        &Expression::literal(default),
+                        batch,
+                        result_type,
+                        ctx,
+                    )
                 })
         }
         (VariadicOperation { .. }, _) => {
@@ -366,29 +856,486 @@ fn evaluate_expression(
     }
 }
 
-#[derive(Debug)]
-pub struct ArrowExpressionHandler;
+/// Each branch of a CASE is evaluated only over the rows that no earlier branch matched, and its
+/// result only over those of them its condition is true for, so that e.g. a division guarded by
+/// `WHEN b <> 0` never divides by zero. The otherwise, if any, is evaluated over the rows left.
+fn evaluate_case(
+    branches: &[(Expression, Expression)],
+    otherwise: Option<&Expression>,
+    batch: &RecordBatch,
+    result_type: Option<&DataType>,
+    ctx: &EvaluationContext<'_>,
+) -> DeltaResult<ArrayRef> {
+    if branches.is_empty() {
+        return Err(Error::invalid_expression(
+            "CASE must have at least one branch",
+        ));
+    }
+    let num_rows = batch.num_rows();
+    // The rows that no branch has matched yet, and their indices in `batch`
+    let mut unmatched = batch.clone();
+    let mut unmatched_rows: Vec<usize> = (0..num_rows).collect();
+    // The result of each branch, along with the indices in `batch` of the rows it is for
+    let mut results: Vec<(ArrayRef, Vec<usize>)> = Vec::with_capacity(branches.len() + 1);
+    for (condition, branch_result) in branches {
+        let condition = evaluate_over_rows(
+            condition,
+            &unmatched,
+            &unmatched_rows,
+            num_rows,
+            Some(&DataType::BOOLEAN),
+            ctx,
+        )?;
+        let condition = condition.as_boolean_opt().ok_or(Error::invalid_expression(
+            "CASE condition must be a boolean",
+        ))?;
+        // A null condition is not true, so does not choose its branch
+        let chosen = null_as_false(condition);
+        let (chosen_rows, rest_rows): (Vec<_>, Vec<_>) = unmatched_rows
+            .iter()
+            .zip(chosen.values())
+            .partition_map(|(row, is_chosen)| match is_chosen {
+                true => Either::Left(*row),
+                false => Either::Right(*row),
+            });
+        let chosen_batch = filter_record_batch(&unmatched, &chosen)?;
+        let result = evaluate_over_rows(
+            branch_result,
+            &chosen_batch,
+            &chosen_rows,
+            num_rows,
+            result_type,
+            ctx,
+        )?;
+        results.push((result, chosen_rows));
+        unmatched = filter_record_batch(&unmatched, &not(&chosen)?)?;
+        unmatched_rows = rest_rows;
+    }
+    let otherwise = match otherwise {
+        Some(otherwise) => evaluate_over_rows(
+            otherwise,
+            &unmatched,
+            &unmatched_rows,
+            num_rows,
+            result_type,
+            ctx,
+        )?,
+        // Without an otherwise, the rows left are null, of the type of the first branch's result
+        None => new_null_array(results[0].0.data_type(), unmatched_rows.len()),
+    };
+    results.push((otherwise, unmatched_rows));
+    // For each row of `batch`, the result that holds its value and its index in that result
+    let mut sources = vec![(0, 0); num_rows];
+    for (result_index, (_, rows)) in results.iter().enumerate() {
+        for (index, row) in rows.iter().enumerate() {
+            sources[*row] = (result_index, index);
+        }
+    }
+    let results: Vec<_> = results.iter().map(|(result, _)| result.as_ref()).collect();
+    Ok(interleave(&results, &sources)?)
+}
 
-impl ExpressionHandler for ArrowExpressionHandler {
-    fn get_evaluator(
+/// Evaluate `expression` over `batch`, which holds the given `rows` of the batch of `num_rows`
+/// rows that `ctx` evaluates over, marking any rows that fail at their index in the full batch.
+fn evaluate_over_rows(
+    expression: &Expression,
+    batch: &RecordBatch,
+    rows: &[usize],
+    num_rows: usize,
+    result_type: Option<&DataType>,
+    ctx: &EvaluationContext<'_>,
+) -> DeltaResult<ArrayRef> {
+    if rows.len() == num_rows {
+        return evaluate_expression_with_context(expression, batch, result_type, ctx);
+    }
+    let rows_ctx = EvaluationContext {
+        options: ctx.options,
+        row_errors: ctx
+            .row_errors
+            .as_ref()
+            .map(|_| RefCell::new(BooleanBuffer::new_unset(rows.len()))),
+        partition_values: ctx.partition_values,
+        allocated: Cell::new(ctx.allocated.get()),
+        like_cache: ctx.like_cache,
+    };
+    let result = evaluate_expression_with_context(expression, batch, result_type, &rows_ctx);
+    ctx.allocated.set(rows_ctx.allocated.get());
+    if let (Some(row_errors), Some(rows_errors)) = (&ctx.row_errors, rows_ctx.row_errors) {
+        let mut errors = BooleanBufferBuilder::new(num_rows);
+        errors.append_buffer(&row_errors.borrow());
+        for index in rows_errors.into_inner().set_indices() {
+            errors.set_bit(rows[index], true);
+        }
+        row_errors.replace(errors.finish());
+    }
+    result
+}
+
+/// Options controlling the semantics of expressions evaluated by the [`ArrowExpressionHandler`].
+#[derive(Debug, Clone, Default)]
+pub struct EvaluationOptions {
+    bitwise_float_comparison: bool,
+    case_insensitive_columns: bool,
+    #[cfg(feature = "unicode-functions")]
+    unicode_normalization: bool,
+    allocation_limit: Option<usize>,
+    table_qualifier: Option<String>,
+}
+
+impl EvaluationOptions {
+    /// Compare floating point values by their bit pattern rather than by their IEEE 754 value.
+    ///
+    /// By default, comparisons follow IEEE 754 equality for zeros, so `-0.0 = +0.0` is true. When
+    /// this is enabled, floats are compared using arrow's native totalOrder semantics instead,
+    /// which treats `-0.0` and `+0.0` as distinct values (with `-0.0 < +0.0`). In both modes `NaN`
+    /// is considered equal to itself and greater than any other value.
+    pub fn with_bitwise_float_comparison(mut self, bitwise_float_comparison: bool) -> Self {
+        self.bitwise_float_comparison = bitwise_float_comparison;
+        self
+    }
+
+    /// Match column references against the names of the batch's columns (and of the fields of
+    /// nested structs) without regard to case. A reference that matches more than one column in
+    /// this way is rejected as ambiguous. Defaults to false, i.e. names are case-sensitive.
+    pub fn with_case_insensitive_columns(mut self, case_insensitive_columns: bool) -> Self {
+        self.case_insensitive_columns = case_insensitive_columns;
+        self
+    }
+
+    /// Resolve column references qualified with the given table name, like `t.a` for a qualifier
+    /// of `t`, to the unqualified columns of the batch, for engines that emit qualified references.
+    /// The qualifier is only stripped if the batch has neither a column of the qualified name nor
+    /// one of the same name as the qualifier, so that a reference to the field of a struct column
+    /// named like the table still resolves to that field. Defaults to no qualifier, i.e. references
+    /// are resolved as they are.
+    pub fn with_table_qualifier(mut self, table_qualifier: impl Into<String>) -> Self {
+        self.table_qualifier = Some(table_qualifier.into());
+        self
+    }
+
+    /// Limit the total size, in bytes, of the intermediate results that a single evaluation of an
+    /// expression may compute, failing with [`Error::AllocationLimitExceeded`] once it goes over.
+    /// This guards against a pathological (e.g. generated or untrusted) expression exhausting
+    /// memory. The size counted is that of each result as a whole, including any buffers it shares
+    /// with its inputs, so it overestimates what was actually allocated. Defaults to no limit.
+    pub fn with_allocation_limit(mut self, limit_bytes: usize) -> Self {
+        self.allocation_limit = Some(limit_bytes);
+        self
+    }
+
+    /// Normalize strings to Unicode Normalization Form C before comparing them, so that
+    /// canonically equivalent strings, like a precomposed `é` and an `e` followed by a combining
+    /// acute accent, compare equal. Defaults to false, i.e. strings are compared byte by byte.
+    ///
+    /// Requires the `unicode-functions` feature.
+    #[cfg(feature = "unicode-functions")]
+    pub fn with_unicode_normalization(mut self, unicode_normalization: bool) -> Self {
+        self.unicode_normalization = unicode_normalization;
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ArrowExpressionHandler {
+    options: EvaluationOptions,
+}
+
+impl ArrowExpressionHandler {
+    /// Create a new handler whose evaluators use the given [`EvaluationOptions`].
+    pub fn new(options: EvaluationOptions) -> Self {
+        Self { options }
+    }
+}
+
+impl ArrowExpressionHandler {
+    /// Like [`ExpressionHandler::get_evaluator`], but returns the [`DefaultExpressionEvaluator`]
+    /// itself, for its methods beyond those of [`ExpressionEvaluator`], like
+    /// [`DefaultExpressionEvaluator::check_supported`].
+    pub fn get_default_evaluator(
         &self,
         schema: SchemaRef,
         expression: Expression,
         output_type: DataType,
-    ) -> Arc<dyn ExpressionEvaluator> {
-        Arc::new(DefaultExpressionEvaluator {
+    ) -> DefaultExpressionEvaluator {
+        DefaultExpressionEvaluator {
             input_schema: schema,
             expression: Box::new(expression),
             output_type,
+            options: self.options.clone(),
+            like_cache: LikeCache::default(),
+        }
+    }
+
+    /// Evaluate several independent expressions over the same batch.
+    ///
+    /// Every column referenced by any of the expressions is resolved from `batch` exactly once and
+    /// then shared by all expressions that reference it, which avoids repeating the lookup (and,
+    /// for nested columns, the walk down the struct path) for each expression.
+    pub fn evaluate_all(
+        &self,
+        batch: &RecordBatch,
+        exprs: &[Expression],
+    ) -> DeltaResult<Vec<ArrayRef>> {
+        let references: HashSet<&str> = exprs.iter().flat_map(Expression::references).collect();
+        let (fields, columns): (Vec<_>, Vec<_>) = references
+            .into_iter()
+            .map(|name| -> DeltaResult<_> {
+                let column = resolve_column(batch, name, &self.options)?;
+                let field = ArrowField::new(name, column.data_type().clone(), column.is_nullable());
+                Ok((field, column))
+            })
+            .process_results(|iter| iter.unzip())?;
+        let options = RecordBatchOptions::new().with_row_count(Some(batch.num_rows()));
+        let resolved = RecordBatch::try_new_with_options(
+            Arc::new(ArrowSchema::new(fields)),
+            columns,
+            &options,
+        )?;
+        // Each column of `resolved` is named exactly like the reference it was resolved for, so
+        // references must match them exactly: resolving `a` against columns for both `a` and `A`
+        // without regard to case would be ambiguous.
+        let exact_options = EvaluationOptions {
+            case_insensitive_columns: false,
+            table_qualifier: None,
+            ..self.options.clone()
+        };
+        exprs
+            .iter()
+            .map(|expr| evaluate_expression_with_options(expr, &resolved, None, &exact_options))
+            .collect()
+    }
+
+    /// Evaluate `expression` over loose `columns` of `num_rows` rows each, rather than over a
+    /// [`RecordBatch`]. Column references are resolved by their name in the map (including, for a
+    /// struct column, paths to its nested fields).
+    pub fn evaluate_columns(
+        &self,
+        expression: &Expression,
+        columns: &HashMap<String, ArrayRef>,
+        num_rows: usize,
+    ) -> DeltaResult<ArrayRef> {
+        let (fields, columns): (Vec<_>, Vec<_>) = columns
+            .iter()
+            .map(|(name, column)| {
+                let field = ArrowField::new(name, column.data_type().clone(), column.is_nullable());
+                (field, column.clone())
+            })
+            .unzip();
+        let options = RecordBatchOptions::new().with_row_count(Some(num_rows));
+        let batch = RecordBatch::try_new_with_options(
+            Arc::new(ArrowSchema::new(fields)),
+            columns,
+            &options,
+        )?;
+        evaluate_expression_with_options(expression, &batch, None, &self.options)
+    }
+
+    /// Evaluate `expression` over `batch`, capturing the rows that fail to evaluate instead of
+    /// failing the evaluation as a whole, e.g. to route bad rows to a dead-letter queue.
+    ///
+    /// Returns the result, in which each failed row is null, along with a mask that is true for
+    /// exactly the failed rows. Only per-row cast failures (like a string that doesn't parse as a
+    /// number) are captured this way; any other error still fails the whole evaluation.
+    pub fn evaluate_with_row_errors(
+        &self,
+        batch: &RecordBatch,
+        expression: &Expression,
+    ) -> DeltaResult<(ArrayRef, BooleanArray)> {
+        let ctx = EvaluationContext::new(&self.options).with_row_errors(batch.num_rows());
+        let result = evaluate_expression_with_context(expression, batch, None, &ctx)?;
+        // Safety: we just created the context with row errors
+        let row_errors = ctx.row_errors.unwrap().into_inner();
+        Ok((result, BooleanArray::new(row_errors, None)))
+    }
+
+    /// Evaluate `expression` over `batch`, which holds the data of a file whose partition columns
+    /// have the given `partition_values`. A reference to a partition column evaluates to its value
+    /// for every row, and any other column reference is resolved from `batch` as usual.
+    ///
+    /// In Delta the values of partition columns are only recorded in the log (and the file path),
+    /// not in the data files, so this is needed to evaluate an expression over both.
+    pub fn evaluate_with_partition_values(
+        &self,
+        batch: &RecordBatch,
+        expression: &Expression,
+        partition_values: &HashMap<String, Scalar>,
+    ) -> DeltaResult<ArrayRef> {
+        let ctx = EvaluationContext::new(&self.options).with_partition_values(partition_values);
+        evaluate_expression_with_context(expression, batch, None, &ctx)
+    }
+
+    /// Evaluate a boolean `predicate` over `batch` and collect the positions of the rows that match
+    /// it (or, if `matching` is false, the rows that don't) into a [`RoaringTreemap`], the same
+    /// representation kernel uses for deletion vectors. A row for which the predicate evaluates to
+    /// null does not match. Positions are relative to the start of `batch`.
+    pub fn evaluate_row_positions(
+        &self,
+        batch: &RecordBatch,
+        predicate: &Expression,
+        matching: bool,
+    ) -> DeltaResult<RoaringTreemap> {
+        let selection = self.evaluate_predicate(batch, predicate)?.matching();
+        let selection = if matching {
+            selection
+        } else {
+            not(&selection)?
+        };
+        Ok(selection.values().set_indices().map(|i| i as u64).collect())
+    }
+
+    /// Evaluate a boolean `predicate` over `batch`, keeping the rows for which it is null distinct
+    /// from those for which it is false. The same result can then be used both to filter rows and
+    /// to skip data, which interpret a null differently, without evaluating the predicate twice.
+    pub fn evaluate_predicate(
+        &self,
+        batch: &RecordBatch,
+        predicate: &Expression,
+    ) -> DeltaResult<PredicateResult> {
+        let result = evaluate_expression_with_options(
+            predicate,
+            batch,
+            Some(&DataType::BOOLEAN),
+            &self.options,
+        )?;
+        Ok(PredicateResult {
+            result: downcast_to_bool(&result)?.clone(),
         })
     }
 }
 
+/// The three-valued result of evaluating a predicate: true where a row matches, false where it
+/// definitely doesn't, and null where it is unknown whether it does (e.g. in SQL a comparison with
+/// a null value).
+#[derive(Debug, Clone)]
+pub struct PredicateResult {
+    result: BooleanArray,
+}
+
+impl PredicateResult {
+    /// The result as is, with its null buffer intact.
+    pub fn result(&self) -> &BooleanArray {
+        &self.result
+    }
+
+    /// True for the rows known to match, treating null as false. This is how to filter rows, e.g.
+    /// for a `WHERE` clause.
+    pub fn matching(&self) -> BooleanArray {
+        null_as_false(&self.result)
+    }
+
+    /// True for the rows that may match, treating null as true. This is how to skip data, e.g.
+    /// files by their stats, where only the data that definitely doesn't match can be skipped.
+    pub fn possibly_matching(&self) -> BooleanArray {
+        match self.result.nulls() {
+            Some(nulls) => {
+                let values = self.result.values() | &!nulls.inner();
+                BooleanArray::new(values, None)
+            }
+            None => self.result.clone(),
+        }
+    }
+}
+
+impl ExpressionHandler for ArrowExpressionHandler {
+    fn get_evaluator(
+        &self,
+        schema: SchemaRef,
+        expression: Expression,
+        output_type: DataType,
+    ) -> Arc<dyn ExpressionEvaluator> {
+        Arc::new(self.get_default_evaluator(schema, expression, output_type))
+    }
+}
+
 #[derive(Debug)]
 pub struct DefaultExpressionEvaluator {
     input_schema: SchemaRef,
     expression: Box<Expression>,
     output_type: DataType,
+    options: EvaluationOptions,
+    like_cache: LikeCache,
+}
+
+impl DefaultExpressionEvaluator {
+    fn evaluate_batch(&self, batch: &RecordBatch) -> DeltaResult<ArrayRef> {
+        let ctx = EvaluationContext::new(&self.options).with_like_cache(&self.like_cache);
+        evaluate_expression_with_context(&self.expression, batch, Some(&self.output_type), &ctx)
+    }
+
+    /// Check that every operation of the expression is supported for the types of its operands
+    /// (e.g. no arithmetic on strings, or `AND` of integers) given the input schema, and that the
+    /// expression produces the output type, without having to evaluate it over any data. Returns
+    /// the error that evaluating would fail with otherwise.
+    ///
+    /// This works by evaluating the expression over an empty batch, which runs all the same type
+    /// checks as evaluating over real data. On top of those, comparing a boolean with a number
+    /// (like `bool_col > 0`) is rejected unless one side is explicitly cast, since it is more
+    /// likely to be a mistake than intended.
+    pub fn check_supported(&self) -> DeltaResult<()> {
+        let input_schema: ArrowSchema = self.input_schema.as_ref().try_into()?;
+        let batch = RecordBatch::new_empty(Arc::new(input_schema));
+        check_boolean_numeric_comparisons(&self.expression, &batch, &self.options)?;
+        self.evaluate(&ArrowEngineData::new(batch)).map(|_| ())
+    }
+
+    /// Evaluate the expression over `batch`, returning the result along with the field that
+    /// describes it, which is all a caller needs to add the result to a batch of their own. The
+    /// field is named after the alias of the expression if it has one (and `output` otherwise),
+    /// has the type of the result, and is nullable unless the expression can never be null over
+    /// the input schema.
+    pub fn evaluate_with_field(&self, batch: &RecordBatch) -> DeltaResult<(ArrayRef, ArrowField)> {
+        let result = self.evaluate_batch(batch)?;
+        let name = match self.expression.as_ref() {
+            Expression::Alias { name, .. } => name.as_str(),
+            _ => "output",
+        };
+        let nullable = self.expression.is_nullable(&self.input_schema);
+        let field = ArrowField::new(name, result.data_type().clone(), nullable);
+        Ok((result, field))
+    }
+
+    /// Evaluate the expression over `batch`, returning the result along with the number of nulls in
+    /// it, e.g. to compute the null count stat of a column in the same pass. The count comes from
+    /// the result's null buffer, so it is computed without another scan of the result.
+    pub fn evaluate_with_null_count(&self, batch: &RecordBatch) -> DeltaResult<(ArrayRef, usize)> {
+        let result = self.evaluate_batch(batch)?;
+        // the logical nulls also account for arrays without a null buffer, like a `NullArray`
+        let null_count = result.logical_nulls().map_or(0, |nulls| nulls.null_count());
+        Ok((result, null_count))
+    }
+}
+
+fn check_boolean_numeric_comparisons(
+    expression: &Expression,
+    empty_batch: &RecordBatch,
+    options: &EvaluationOptions,
+) -> DeltaResult<()> {
+    match expression {
+        Expression::BinaryOperation { op, left, right } if op.is_comparison() => {
+            let left_type = evaluate_expression_with_options(left, empty_batch, None, options)?
+                .data_type()
+                .clone();
+            let right_type = evaluate_expression_with_options(right, empty_batch, None, options)?
+                .data_type()
+                .clone();
+            let is_boolean_and_numeric = |a: &ArrowDataType, b: &ArrowDataType| {
+                *a == ArrowDataType::Boolean && b.is_numeric()
+            };
+            if is_boolean_and_numeric(&left_type, &right_type)
+                || is_boolean_and_numeric(&right_type, &left_type)
+            {
+                return Err(Error::invalid_expression(format!(
+                    "Cannot compare {left_type} with {right_type} in {expression}, cast one side \
+                     explicitly to compare them"
+                )));
+            }
+        }
+        _ => {}
+    }
+    expression
+        .children()
+        .into_iter()
+        .try_for_each(|child| check_boolean_numeric_comparisons(child, empty_batch, options))
 }
 
 impl ExpressionEvaluator for DefaultExpressionEvaluator {
@@ -407,7 +1354,7 @@ impl ExpressionEvaluator for DefaultExpressionEvaluator {
         //         batch.schema()
         //     )));
         // };
-        let array_ref = evaluate_expression(&self.expression, batch, Some(&self.output_type))?;
+        let array_ref = self.evaluate_batch(batch)?;
         let arrow_type: ArrowDataType = ArrowDataType::try_from(&self.output_type)?;
         let batch: RecordBatch = if let DataType::Struct(_) = self.output_type {
             array_ref
@@ -422,17 +1369,49 @@ impl ExpressionEvaluator for DefaultExpressionEvaluator {
     }
 }
 
+/// Evaluate `evaluator` over each of `batches`, sharing the evaluator between up to `num_threads`
+/// threads. The results are returned in the same order as `batches`, and if evaluating any batch
+/// fails, one of the errors is returned.
+pub fn evaluate_parallel(
+    evaluator: &dyn ExpressionEvaluator,
+    batches: &[Box<dyn EngineData>],
+    num_threads: usize,
+) -> DeltaResult<Vec<Box<dyn EngineData>>> {
+    let chunk_size = batches.len().div_ceil(num_threads.max(1)).max(1);
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = batches
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|batch| evaluator.evaluate(batch.as_ref()))
+                        .collect::<DeltaResult<Vec<_>>>()
+                })
+            })
+            .collect();
+        let mut results = Vec::with_capacity(batches.len());
+        for handle in handles {
+            let chunk_results = handle
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic))?;
+            results.extend(chunk_results);
+        }
+        Ok(results)
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use std::ops::{Add, Div, Mul, Sub};
 
-    use arrow_array::{GenericStringArray, Int32Array};
+    use arrow_array::{DictionaryArray, GenericStringArray, Int32Array, NullArray};
     use arrow_buffer::ScalarBuffer;
     use arrow_schema::{DataType, Field, Fields, Schema};
 
     use super::*;
     use crate::expressions::*;
-    use crate::schema::ArrayType;
+    use crate::schema::{ArrayType, MapType};
     use crate::DataType as DeltaDataTypes;
 
     #[test]
@@ -602,6 +1581,49 @@ mod tests {
         assert_eq!(results.as_ref(), &values);
     }
 
+    #[test]
+    fn test_extract_column_from_null_struct() {
+        let field = Arc::new(Field::new("a", DataType::Int32, true));
+        let values = Int32Array::from(vec![Some(1), None, Some(3), Some(4)]);
+        let struct_array = StructArray::new(
+            Fields::from(vec![field.clone()]),
+            vec![Arc::new(values)],
+            Some(NullBuffer::from(vec![true, true, false, true])),
+        );
+        let schema = Schema::new(vec![Field::new(
+            "b",
+            DataType::Struct(Fields::from(vec![field])),
+            true,
+        )]);
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(struct_array)]).unwrap();
+
+        let column = Expression::column("b.a");
+        let results = evaluate_expression(&column, &batch, None).unwrap();
+        let expected = Int32Array::from(vec![Some(1), None, None, Some(4)]);
+        assert_eq!(results.as_ref(), &expected);
+
+        // nulls also propagate through a slice of the batch
+        let results = evaluate_expression(&column, &batch.slice(1, 3), None).unwrap();
+        let expected = Int32Array::from(vec![None, None, Some(4)]);
+        assert_eq!(results.as_ref(), &expected);
+
+        // a child of the null type stays as it is
+        let field = Arc::new(Field::new("n", DataType::Null, true));
+        let struct_array = StructArray::new(
+            Fields::from(vec![field.clone()]),
+            vec![Arc::new(NullArray::new(4))],
+            Some(NullBuffer::from(vec![true, true, false, true])),
+        );
+        let schema = Schema::new(vec![Field::new(
+            "s",
+            DataType::Struct(Fields::from(vec![field])),
+            true,
+        )]);
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(struct_array)]).unwrap();
+        let results = evaluate_expression(&Expression::column("s.n"), &batch, None).unwrap();
+        assert_eq!(results.as_ref(), &NullArray::new(4));
+    }
+
     #[test]
     fn test_binary_op_scalar() {
         let schema = Schema::new(vec![Field::new("a", DataType::Int32, false)]);
@@ -750,4 +1772,1045 @@ mod tests {
         let expected = Arc::new(BooleanArray::from(vec![true, false]));
         assert_eq!(results.as_ref(), expected.as_ref());
     }
+
+    #[test]
+    fn test_float_zero_comparison() {
+        let schema = Schema::new(vec![Field::new("a", DataType::Float32, false)]);
+        let values = Float32Array::from(vec![-0.0, 0.0, 1.0]);
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(values)]).unwrap();
+        let expression = Expression::column("a").eq(Expression::literal(Scalar::Float(0.0)));
+
+        let results = evaluate_expression(&expression, &batch, None).unwrap();
+        let expected = Arc::new(BooleanArray::from(vec![true, true, false]));
+        assert_eq!(results.as_ref(), expected.as_ref());
+
+        let options = EvaluationOptions::default().with_bitwise_float_comparison(true);
+        let results =
+            evaluate_expression_with_options(&expression, &batch, None, &options).unwrap();
+        let expected = Arc::new(BooleanArray::from(vec![false, true, false]));
+        assert_eq!(results.as_ref(), expected.as_ref());
+
+        let expression = Expression::column("a").lt(Expression::literal(Scalar::Float(0.0)));
+        let results =
+            evaluate_expression_with_options(&expression, &batch, None, &options).unwrap();
+        let expected = Arc::new(BooleanArray::from(vec![true, false, false]));
+        assert_eq!(results.as_ref(), expected.as_ref());
+    }
+
+    #[test]
+    fn test_logical_literal_short_circuit() {
+        let schema = Schema::new(vec![Field::new("a", DataType::Boolean, true)]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(BooleanArray::from(vec![
+                Some(true),
+                Some(false),
+                None,
+            ]))],
+        )
+        .unwrap();
+        let column = Expression::column("a");
+        let options = EvaluationOptions::default();
+        let cases = [
+            (
+                column.clone().and(Expression::literal(true)),
+                vec![Some(true), Some(false), None],
+            ),
+            (
+                column.clone().and(Expression::literal(false)),
+                vec![Some(false), Some(false), Some(false)],
+            ),
+            (
+                column.clone().or(Expression::literal(true)),
+                vec![Some(true), Some(true), Some(true)],
+            ),
+            (
+                column.or(Expression::literal(false)),
+                vec![Some(true), Some(false), None],
+            ),
+        ];
+        for (expression, expected) in cases {
+            let results =
+                evaluate_expression_with_options(&expression, &batch, None, &options).unwrap();
+            assert_eq!(
+                results.as_ref(),
+                &BooleanArray::from(expected),
+                "{expression}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_logical_short_circuit_checks_operands() {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Boolean, true),
+            Field::new("i", DataType::Int32, true),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(BooleanArray::from(vec![Some(true), None])),
+                Arc::new(Int32Array::from(vec![1, 2])),
+            ],
+        )
+        .unwrap();
+        let options = EvaluationOptions::default();
+        let cases = [
+            Expression::column("missing").and(Expression::literal(false)),
+            Expression::column("missing").or(Expression::literal(true)),
+            Expression::column("i").and(Expression::literal(false)),
+        ];
+        for expression in cases {
+            let result = evaluate_expression_with_options(&expression, &batch, None, &options);
+            assert!(result.is_err(), "{expression}");
+        }
+
+        let expression = Expression::column("a").and(Expression::literal(false));
+        let result = evaluate_expression_with_options(&expression, &batch, None, &options).unwrap();
+        assert_eq!(result.as_ref(), &BooleanArray::from(vec![false, false]));
+    }
+
+    #[test]
+    fn test_unsupported_data_type() {
+        let map_type = MapType::new(DeltaDataTypes::STRING, DeltaDataTypes::STRING, true);
+        let result = Scalar::Null(map_type.into()).to_array(1);
+        assert!(matches!(
+            result,
+            Err(Error::UnsupportedDataType {
+                data_type: DeltaDataTypes::Map(_)
+            })
+        ));
+
+        let decimal = DeltaDataTypes::Primitive(PrimitiveType::Decimal(39, 2));
+        let result: DeltaResult<ArrowDataType> = (&decimal).try_into().map_err(Error::from);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Unsupported data type: decimal(39,2)"
+        );
+    }
+
+    #[test]
+    fn test_evaluate_all() {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, false),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 3, 6])),
+                Arc::new(Int32Array::from(vec![2, 1, 2])),
+            ],
+        )
+        .unwrap();
+        let exprs = [
+            Expression::column("a").gt(Expression::literal(1)),
+            Expression::column("a").lt(Expression::literal(5)),
+            Expression::column("b").eq(Expression::literal(2)),
+        ];
+
+        let results = ArrowExpressionHandler::default()
+            .evaluate_all(&batch, &exprs)
+            .unwrap();
+        let expected = [
+            BooleanArray::from(vec![false, true, true]),
+            BooleanArray::from(vec![true, true, false]),
+            BooleanArray::from(vec![true, false, true]),
+        ];
+        assert_eq!(results.len(), expected.len());
+        for (result, expected) in results.iter().zip(expected.iter()) {
+            assert_eq!(result.as_ref(), expected);
+        }
+    }
+
+    #[test]
+    fn test_evaluate_all_mixed_case_references() {
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, false)]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(Int32Array::from(vec![1, 3, 6]))],
+        )
+        .unwrap();
+        let exprs = [
+            Expression::column("a").gt(Expression::literal(1)),
+            Expression::column("A").lt(Expression::literal(5)),
+        ];
+
+        let handler = ArrowExpressionHandler::new(
+            EvaluationOptions::default().with_case_insensitive_columns(true),
+        );
+        let results = handler.evaluate_all(&batch, &exprs).unwrap();
+        assert_eq!(
+            results[0].as_ref(),
+            &BooleanArray::from(vec![false, true, true])
+        );
+        assert_eq!(
+            results[1].as_ref(),
+            &BooleanArray::from(vec![true, true, false])
+        );
+    }
+
+    #[test]
+    fn test_evaluate_filtered_batch() {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, false),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Int32Array::from(vec![0, 2, 1, 3, 5])),
+                Arc::new(Int32Array::from(vec![10, 20, 30, 40, 50])),
+            ],
+        )
+        .unwrap();
+        let options = EvaluationOptions::default();
+        let predicate = Expression::column("a").gt(Expression::literal(1));
+        let projection = Expression::column("b").add(Expression::literal(1));
+
+        let selection =
+            evaluate_expression_with_options(&predicate, &batch, None, &options).unwrap();
+        let filtered =
+            arrow_select::filter::filter_record_batch(&batch, selection.as_boolean()).unwrap();
+        let results =
+            evaluate_expression_with_options(&projection, &filtered, None, &options).unwrap();
+        assert_eq!(results.as_ref(), &Int32Array::from(vec![21, 41, 51]));
+
+        // slicing, unlike filtering, leaves a non-zero offset into the original buffers
+        let sliced = batch.slice(2, 3);
+        let results =
+            evaluate_expression_with_options(&projection, &sliced, None, &options).unwrap();
+        assert_eq!(results.as_ref(), &Int32Array::from(vec![31, 41, 51]));
+    }
+
+    #[test]
+    fn test_evaluate_row_positions() {
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, true)]);
+        let values = Int32Array::from(vec![Some(1), Some(3), None, Some(5), Some(2)]);
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(values)]).unwrap();
+        let predicate = Expression::column("a").gt(Expression::literal(2));
+        let handler = ArrowExpressionHandler::default();
+
+        let matching = handler
+            .evaluate_row_positions(&batch, &predicate, true)
+            .unwrap();
+        assert_eq!(matching.iter().collect::<Vec<_>>(), vec![1, 3]);
+        assert!(matching.contains(3));
+        assert!(!matching.contains(2));
+
+        let non_matching = handler
+            .evaluate_row_positions(&batch, &predicate, false)
+            .unwrap();
+        assert_eq!(non_matching.iter().collect::<Vec<_>>(), vec![0, 2, 4]);
+    }
+
+    #[test]
+    fn test_case_insensitive_columns() {
+        let field = |name| Field::new(name, DataType::Int32, false);
+        let values: ArrayRef = Arc::new(Int32Array::from(vec![1, 2, 3]));
+        let batch = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![field("a")])),
+            vec![values.clone()],
+        )
+        .unwrap();
+        let column = Expression::column("A");
+
+        let result = evaluate_expression(&column, &batch, None);
+        assert!(matches!(result, Err(Error::MissingColumn(_))));
+
+        let options = EvaluationOptions::default().with_case_insensitive_columns(true);
+        let result = evaluate_expression_with_options(&column, &batch, None, &options).unwrap();
+        assert_eq!(result.as_ref(), values.as_ref());
+
+        let schema = Schema::new(vec![field("a"), field("A")]);
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![values.clone(), values]).unwrap();
+        let result = evaluate_expression_with_options(&column, &batch, None, &options);
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Ambiguous reference to field A"));
+    }
+
+    #[test]
+    fn test_decimal_float_comparison() {
+        let schema = Schema::new(vec![Field::new("a", DataType::Decimal128(10, 2), false)]);
+        let values = Decimal128Array::from(vec![150, 250, 300])
+            .with_precision_and_scale(10, 2)
+            .unwrap();
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(values)]).unwrap();
+        let column = Expression::column("a");
+        let lit = Expression::literal(Scalar::Double(2.5));
+        let options = EvaluationOptions::default();
+
+        let cases = [
+            (column.clone().eq(lit.clone()), vec![false, true, false]),
+            (column.clone().gt(lit.clone()), vec![false, false, true]),
+            (lit.lt(column), vec![false, false, true]),
+        ];
+        for (expression, expected) in cases {
+            let results =
+                evaluate_expression_with_options(&expression, &batch, None, &options).unwrap();
+            assert_eq!(
+                results.as_ref(),
+                &BooleanArray::from(expected),
+                "{expression}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_evaluate_with_row_errors() {
+        let schema = Schema::new(vec![Field::new("a", DataType::Utf8, true)]);
+        let values = StringArray::from(vec![Some("1"), Some("x"), None, Some("3")]);
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(values)]).unwrap();
+        let expression = Expression::column("a").cast(DeltaDataTypes::INTEGER);
+
+        let result = evaluate_expression(&expression, &batch, None);
+        assert!(result.is_err());
+
+        let (result, row_errors) = ArrowExpressionHandler::default()
+            .evaluate_with_row_errors(&batch, &expression)
+            .unwrap();
+        let expected = Int32Array::from(vec![Some(1), None, None, Some(3)]);
+        assert_eq!(result.as_ref(), &expected);
+        assert_eq!(
+            row_errors,
+            BooleanArray::from(vec![false, true, false, false])
+        );
+    }
+
+    #[test]
+    fn test_evaluate_parallel() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<DefaultExpressionEvaluator>();
+
+        let input_schema = Arc::new(crate::schema::StructType::new(vec![
+            crate::schema::StructField::new("a", DeltaDataTypes::INTEGER, false),
+        ]));
+        let evaluator = ArrowExpressionHandler::default().get_evaluator(
+            input_schema,
+            Expression::column("a").add(Expression::literal(1)),
+            DeltaDataTypes::INTEGER,
+        );
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let batches: Vec<Box<dyn EngineData>> = (0..64)
+            .map(|i| {
+                let values = Int32Array::from_iter_values(i * 10..i * 10 + 10);
+                let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(values)]).unwrap();
+                Box::new(ArrowEngineData::new(batch)) as _
+            })
+            .collect();
+
+        for num_threads in [1, 4, 100] {
+            let results = evaluate_parallel(evaluator.as_ref(), &batches, num_threads).unwrap();
+            assert_eq!(results.len(), batches.len());
+            for (i, result) in (0..).zip(results) {
+                let batch = ArrowEngineData::try_from_engine_data(result)
+                    .unwrap()
+                    .record_batch()
+                    .clone();
+                let expected = Int32Array::from_iter_values(i * 10 + 1..i * 10 + 11);
+                assert_eq!(batch.column(0).as_ref(), &expected);
+            }
+        }
+
+        let results = evaluate_parallel(evaluator.as_ref(), &[], 4).unwrap();
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn test_int_decimal_arithmetic() {
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, false)]);
+        let values = Int32Array::from(vec![1, 2, -3]);
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(values)]).unwrap();
+        let column = Expression::column("a");
+        let lit = Expression::literal(Scalar::Decimal(150, 10, 2));
+        let options = EvaluationOptions::default();
+
+        // multiplying by the promoted integer adds its scale to the scale of the result
+        let cases = [
+            (column.clone().add(lit.clone()), 2, vec![250, 350, -150]),
+            (lit.clone().sub(column.clone()), 2, vec![50, -50, 450]),
+            (column.mul(lit), 4, vec![15000, 30000, -45000]),
+        ];
+        for (expression, expected_scale, expected) in cases {
+            let results =
+                evaluate_expression_with_options(&expression, &batch, None, &options).unwrap();
+            let ArrowDataType::Decimal128(_, scale) = results.data_type() else {
+                panic!("{expression} is not a decimal: {}", results.data_type());
+            };
+            assert_eq!(*scale, expected_scale, "{expression}");
+            let results = results.as_primitive::<Decimal128Type>();
+            assert_eq!(results.values().to_vec(), expected, "{expression}");
+        }
+    }
+
+    #[test]
+    fn test_duplicate_column_names() {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, false),
+            Field::new("a", DataType::Int32, false),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2])),
+                Arc::new(Int32Array::from(vec![3, 4])),
+                Arc::new(Int32Array::from(vec![5, 6])),
+            ],
+        )
+        .unwrap();
+        let options = EvaluationOptions::default();
+
+        let result =
+            evaluate_expression_with_options(&Expression::column("a"), &batch, None, &options);
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Ambiguous reference to field a, which matches several fields"));
+
+        // unambiguous columns of the same batch can still be referenced
+        let result =
+            evaluate_expression_with_options(&Expression::column("b"), &batch, None, &options)
+                .unwrap();
+        assert_eq!(result.as_ref(), &Int32Array::from(vec![3, 4]));
+    }
+
+    #[cfg(feature = "unicode-functions")]
+    #[test]
+    fn test_unicode_normalization() {
+        let schema = Schema::new(vec![Field::new("a", DataType::Utf8, false)]);
+        // a precomposed "é", and an "e" followed by a combining acute accent
+        let values = StringArray::from(vec!["caf\u{e9}", "cafe\u{301}", "cafe"]);
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(values)]).unwrap();
+        let expression = Expression::column("a").eq(Expression::literal("caf\u{e9}"));
+
+        let results = evaluate_expression(&expression, &batch, None).unwrap();
+        let expected = BooleanArray::from(vec![true, false, false]);
+        assert_eq!(results.as_ref(), &expected);
+
+        let options = EvaluationOptions::default().with_unicode_normalization(true);
+        let results =
+            evaluate_expression_with_options(&expression, &batch, None, &options).unwrap();
+        let expected = BooleanArray::from(vec![true, true, false]);
+        assert_eq!(results.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_sliced_decimal_comparison() {
+        let schema = Schema::new(vec![Field::new("a", DataType::Decimal128(10, 2), false)]);
+        let values = Decimal128Array::from(vec![100, 150, 250, 300])
+            .with_precision_and_scale(10, 2)
+            .unwrap();
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(values)]).unwrap();
+        let batch = batch.slice(1, 3);
+        assert_eq!(batch.column(0).data_type(), &DataType::Decimal128(10, 2));
+        let options = EvaluationOptions::default();
+
+        // literals of the same type as the column, of a lower precision and scale, and of a higher
+        // scale
+        let lits = [
+            Scalar::Decimal(150, 10, 2),
+            Scalar::Decimal(15, 5, 1),
+            Scalar::Decimal(15000, 7, 4),
+        ];
+        for lit in lits {
+            let expression = Expression::column("a").gt(Expression::literal(lit.clone()));
+            let results =
+                evaluate_expression_with_options(&expression, &batch, None, &options).unwrap();
+            let expected = BooleanArray::from(vec![false, true, true]);
+            assert_eq!(results.as_ref(), &expected, "{expression}");
+        }
+    }
+
+    #[test]
+    fn test_case() {
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, true)]);
+        let values = Int32Array::from(vec![Some(5), Some(1), Some(-1), None]);
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(values)]).unwrap();
+        let column = Expression::column("a");
+        let options = EvaluationOptions::default();
+
+        let case = Expression::when(column.clone().gt(Expression::literal(2)), "big".into())
+            .when(column.clone().gt(Expression::literal(0)), "small".into());
+        let expression = case.clone().otherwise("other".into());
+        let results =
+            evaluate_expression_with_options(&expression, &batch, None, &options).unwrap();
+        let expected = StringArray::from(vec!["big", "small", "other", "other"]);
+        assert_eq!(results.as_ref(), &expected);
+
+        let results =
+            evaluate_expression_with_options(&case.end(), &batch, None, &options).unwrap();
+        let expected = StringArray::from(vec![Some("big"), Some("small"), None, None]);
+        assert_eq!(results.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_case_guarded_branches() {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, false),
+            Field::new("s", DataType::Utf8, false),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Int32Array::from(vec![6, 1, 4])),
+                Arc::new(Int32Array::from(vec![2, 0, 4])),
+                Arc::new(StringArray::from(vec!["x", "7", "x"])),
+            ],
+        )
+        .unwrap();
+        let handler = ArrowExpressionHandler::default();
+        let a = Expression::column("a");
+        let b = Expression::column("b");
+        let s = Expression::column("s");
+
+        // the division is only evaluated for the rows where `b` is not zero
+        let division = Expression::when(b.clone().ne(Expression::literal(0)), a / b)
+            .otherwise(Expression::literal(0));
+        let expected = Int32Array::from(vec![3, 0, 1]);
+        let result = evaluate_expression(&division, &batch, None).unwrap();
+        assert_eq!(result.as_ref(), &expected);
+        let (result, row_errors) = handler.evaluate_with_row_errors(&batch, &division).unwrap();
+        assert_eq!(result.as_ref(), &expected);
+        assert_eq!(row_errors, BooleanArray::from(vec![false; 3]));
+
+        // the cast is only evaluated for the rows whose string is not 'x', and a later condition
+        // only for the rows an earlier one didn't match
+        let cast = Expression::when(
+            s.clone().eq(Expression::literal("x")),
+            Expression::literal(0),
+        )
+        .when(
+            s.clone()
+                .cast(DeltaDataTypes::INTEGER)
+                .gt(Expression::literal(5)),
+            s.cast(DeltaDataTypes::INTEGER),
+        )
+        .end();
+        let expected = Int32Array::from(vec![Some(0), Some(7), Some(0)]);
+        let result = evaluate_expression(&cast, &batch, None).unwrap();
+        assert_eq!(result.as_ref(), &expected);
+        let (result, row_errors) = handler.evaluate_with_row_errors(&batch, &cast).unwrap();
+        assert_eq!(result.as_ref(), &expected);
+        assert_eq!(row_errors, BooleanArray::from(vec![false; 3]));
+
+        // a row that a branch does evaluate for is still marked as failed at its own index
+        let batch = batch.slice(1, 2);
+        let cast = Expression::when(
+            Expression::column("a").gt(Expression::literal(2)),
+            Expression::column("s").cast(DeltaDataTypes::INTEGER),
+        )
+        .end();
+        assert!(evaluate_expression(&cast, &batch, None).is_err());
+        let (result, row_errors) = handler.evaluate_with_row_errors(&batch, &cast).unwrap();
+        assert_eq!(result.as_ref(), &Int32Array::from(vec![None::<i32>; 2]));
+        assert_eq!(row_errors, BooleanArray::from(vec![false, true]));
+    }
+
+    #[test]
+    fn test_evaluate_with_partition_values() {
+        let schema = Schema::new(vec![Field::new("data_col", DataType::Int32, false)]);
+        let values = Int32Array::from(vec![0, 1, 2]);
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(values)]).unwrap();
+        let expression = Expression::column("part_col")
+            .eq(Expression::literal("x"))
+            .and(Expression::column("data_col").gt(Expression::literal(1)));
+        let handler = ArrowExpressionHandler::default();
+
+        let partition_values = HashMap::from([("part_col".to_string(), Scalar::from("x"))]);
+        let results = handler
+            .evaluate_with_partition_values(&batch, &expression, &partition_values)
+            .unwrap();
+        let expected = BooleanArray::from(vec![false, false, true]);
+        assert_eq!(results.as_ref(), &expected);
+
+        let partition_values = HashMap::from([("part_col".to_string(), Scalar::from("y"))]);
+        let results = handler
+            .evaluate_with_partition_values(&batch, &expression, &partition_values)
+            .unwrap();
+        let expected = BooleanArray::from(vec![false, false, false]);
+        assert_eq!(results.as_ref(), &expected);
+
+        // without the partition values, the partition column is missing
+        let result = evaluate_expression(&expression, &batch, None);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_duration_arithmetic() {
+        let timestamp_type = DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()));
+        let schema = Schema::new(vec![
+            Field::new("a", timestamp_type.clone(), false),
+            Field::new("b", timestamp_type.clone(), false),
+        ]);
+        let a = TimestampMicrosecondArray::from(vec![1_000, 5_000, 9_000]).with_timezone("UTC");
+        let b = TimestampMicrosecondArray::from(vec![3_000, 4_000, 1_000]).with_timezone("UTC");
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(a), Arc::new(b)]).unwrap();
+        let a = Expression::column("a");
+        let b = Expression::column("b");
+        let options = EvaluationOptions::default();
+
+        let expression = (a.clone() - b.clone()).gt(b.clone() - a.clone());
+        let results =
+            evaluate_expression_with_options(&expression, &batch, None, &options).unwrap();
+        assert_eq!(
+            results.as_ref(),
+            &BooleanArray::from(vec![false, true, true])
+        );
+
+        let expression = b.clone() + (a.clone() - b.clone());
+        let results =
+            evaluate_expression_with_options(&expression, &batch, None, &options).unwrap();
+        assert_eq!(results.as_ref(), batch.column(0).as_ref());
+
+        let expression = a.clone() - (a.clone() - b.clone());
+        let results =
+            evaluate_expression_with_options(&expression, &batch, None, &options).unwrap();
+        assert_eq!(results.as_ref(), batch.column(1).as_ref());
+
+        let expression = (a.clone() - b.clone()).gt(Expression::literal(5));
+        let result = evaluate_expression_with_options(&expression, &batch, None, &options);
+        assert!(matches!(result, Err(Error::InvalidExpressionEvaluation(_))));
+
+        let expression = (a.clone() - b).gt(a);
+        let result = evaluate_expression_with_options(&expression, &batch, None, &options);
+        assert!(matches!(result, Err(Error::InvalidExpressionEvaluation(_))));
+    }
+
+    #[test]
+    fn test_check_supported() {
+        let input_schema = Arc::new(crate::schema::StructType::new(vec![
+            crate::schema::StructField::new("i", DeltaDataTypes::INTEGER, false),
+            crate::schema::StructField::new("s", DeltaDataTypes::STRING, false),
+        ]));
+        let handler = ArrowExpressionHandler::default();
+        let evaluator = |expression: Expression, output_type: DeltaDataTypes| {
+            handler.get_default_evaluator(input_schema.clone(), expression, output_type)
+        };
+        let i = Expression::column("i");
+        let s = Expression::column("s");
+
+        let supported = [
+            (i.clone() + Expression::literal(1), DeltaDataTypes::INTEGER),
+            (
+                s.clone().eq(Expression::literal("x")),
+                DeltaDataTypes::BOOLEAN,
+            ),
+            (
+                s.clone()
+                    .split_part(Expression::literal("/"), Expression::literal(1)),
+                DeltaDataTypes::STRING,
+            ),
+        ];
+        for (expression, output_type) in supported {
+            let result = evaluator(expression.clone(), output_type).check_supported();
+            assert!(result.is_ok(), "{expression}: {result:?}");
+        }
+
+        let unsupported = [
+            (s.clone() + Expression::literal(1), DeltaDataTypes::STRING),
+            (s.clone().gt(i.clone()), DeltaDataTypes::BOOLEAN),
+            (i.clone().and(s.clone().is_null()), DeltaDataTypes::BOOLEAN),
+            (
+                i.clone()
+                    .split_part(Expression::literal("/"), Expression::literal(1)),
+                DeltaDataTypes::STRING,
+            ),
+            (
+                i.clone().is_null().gt(Expression::literal(0)),
+                DeltaDataTypes::BOOLEAN,
+            ),
+            // the expression is fine, but doesn't produce the output type
+            (i.clone() + Expression::literal(1), DeltaDataTypes::STRING),
+        ];
+        for (expression, output_type) in unsupported {
+            let result = evaluator(expression.clone(), output_type).check_supported();
+            assert!(result.is_err(), "{expression}");
+        }
+    }
+
+    #[test]
+    fn test_evaluate_with_and_without_null_buffer() {
+        let field = Field::new("a", DataType::Int32, true);
+        let values = ScalarBuffer::from(vec![1, 2, 3]);
+        let batch_with_nulls = |nulls: Option<NullBuffer>| {
+            let a = Int32Array::new(values.clone(), nulls);
+            let s = StructArray::new(vec![field.clone()].into(), vec![Arc::new(a.clone())], None);
+            let schema = Arc::new(Schema::new(vec![
+                field.clone(),
+                Field::new("s", s.data_type().clone(), true),
+            ]));
+            RecordBatch::try_new(schema, vec![Arc::new(a), Arc::new(s)]).unwrap()
+        };
+        let without_nulls = batch_with_nulls(None);
+        let all_valid = batch_with_nulls(Some(NullBuffer::new_valid(3)));
+        assert!(all_valid.column(0).nulls().is_some());
+
+        let a = Expression::column("a");
+        let expressions = [
+            a.clone(),
+            Expression::column("s.a"),
+            a.clone() + Expression::literal(1),
+            a.clone().gt(Expression::literal(1)),
+            a.clone().is_null(),
+            !a.clone().is_null(),
+            a.clone()
+                .gt(Expression::literal(1))
+                .and(a.clone().lt(Expression::literal(3))),
+            Expression::coalesce([a.clone(), Expression::literal(0)]),
+            Expression::when(a.clone().gt(Expression::literal(2)), a.clone()).end(),
+        ];
+        let options = EvaluationOptions::default();
+        for expression in expressions {
+            let expected =
+                evaluate_expression_with_options(&expression, &without_nulls, None, &options)
+                    .unwrap();
+            let result =
+                evaluate_expression_with_options(&expression, &all_valid, None, &options).unwrap();
+            assert_eq!(result.as_ref(), expected.as_ref(), "{expression}");
+            assert_eq!(result.null_count(), expected.null_count(), "{expression}");
+        }
+
+        let handler = ArrowExpressionHandler::default();
+        let predicate = a.gt(Expression::literal(1));
+        let expected = handler
+            .evaluate_row_positions(&without_nulls, &predicate, true)
+            .unwrap();
+        let result = handler
+            .evaluate_row_positions(&all_valid, &predicate, true)
+            .unwrap();
+        assert_eq!(result, expected);
+        assert_eq!(result, RoaringTreemap::from_iter([1, 2]));
+    }
+
+    #[test]
+    fn test_array_literal_to_array() {
+        let array_type = ArrayType::new(DeltaDataTypes::INTEGER, false);
+        let data = ArrayData::new(array_type.clone(), vec![Scalar::from(1), Scalar::from(2)]);
+        let result = Scalar::Array(data).to_array(3).unwrap();
+        let result = result.as_list::<i32>();
+        assert_eq!(result.len(), 3);
+        for row in result.iter() {
+            assert_eq!(row.unwrap().as_ref(), &Int32Array::from(vec![1, 2]));
+        }
+
+        let data = ArrayData::new(array_type, vec![]);
+        let result = Scalar::Array(data).to_array(2).unwrap();
+        assert_eq!(result.as_list::<i32>().value_offsets(), &[0, 0, 0]);
+    }
+
+    #[test]
+    fn test_needs_large_list_offsets() {
+        let max = i32::MAX as usize;
+        assert!(!needs_large_list_offsets(1, max));
+        assert!(needs_large_list_offsets(1, max + 1));
+        assert!(!needs_large_list_offsets(max / 2, 2));
+        assert!(needs_large_list_offsets(max / 2 + 1, 2));
+        assert!(needs_large_list_offsets(usize::MAX, 2));
+        assert!(!needs_large_list_offsets(0, usize::MAX));
+    }
+
+    #[test]
+    fn test_evaluate_columns() {
+        let columns: HashMap<String, ArrayRef> = HashMap::from([
+            (
+                "a".to_string(),
+                Arc::new(Int32Array::from(vec![1, 2, 3])) as _,
+            ),
+            (
+                "b".to_string(),
+                Arc::new(Int32Array::from(vec![10, 20, 30])) as _,
+            ),
+        ]);
+        let handler = ArrowExpressionHandler::default();
+        let expression = Expression::column("a") + Expression::column("b");
+        let result = handler.evaluate_columns(&expression, &columns, 3).unwrap();
+        assert_eq!(result.as_ref(), &Int32Array::from(vec![11, 22, 33]));
+
+        // an expression without any column references still has a row per input row
+        let result = handler
+            .evaluate_columns(&Expression::literal(1), &HashMap::new(), 2)
+            .unwrap();
+        assert_eq!(result.as_ref(), &Int32Array::from(vec![1, 1]));
+
+        let result = handler.evaluate_columns(&expression, &columns, 2);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_check_supported_boolean_numeric_comparison() {
+        let input_schema = Arc::new(crate::schema::StructType::new(vec![
+            crate::schema::StructField::new("b", DeltaDataTypes::BOOLEAN, false),
+            crate::schema::StructField::new("i", DeltaDataTypes::INTEGER, false),
+        ]));
+        let handler = ArrowExpressionHandler::default();
+        let evaluator = |expression: Expression| {
+            handler.get_default_evaluator(input_schema.clone(), expression, DeltaDataTypes::BOOLEAN)
+        };
+        let b = Expression::column("b");
+        let i = Expression::column("i");
+
+        let result = evaluator(b.clone().gt(Expression::literal(0))).check_supported();
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Invalid expression evaluation: Cannot compare Boolean with Int32 in Column(b) > 0, \
+             cast one side explicitly to compare them"
+        );
+        let result = evaluator(i.clone().eq(b.clone()).or(b.clone())).check_supported();
+        assert!(result.is_err());
+
+        let cast = b
+            .clone()
+            .cast(DeltaDataTypes::INTEGER)
+            .gt(Expression::literal(0));
+        assert!(evaluator(cast).check_supported().is_ok());
+        let comparison = b.eq(i.gt(Expression::literal(0)));
+        assert!(evaluator(comparison).check_supported().is_ok());
+    }
+
+    #[test]
+    fn test_allocation_limit() {
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, false)]);
+        let values = Int32Array::from_iter_values(0..1000);
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(values)]).unwrap();
+        // each comparison computes a literal array of 1000 ints and a boolean array
+        let expression = Expression::and_from(
+            (0..50).map(|i| Expression::column("a").ne(Expression::literal(i))),
+        );
+
+        let options = EvaluationOptions::default();
+        assert!(evaluate_expression_with_options(&expression, &batch, None, &options).is_ok());
+
+        let options = EvaluationOptions::default().with_allocation_limit(100_000);
+        let result = evaluate_expression_with_options(&expression, &batch, None, &options);
+        assert!(matches!(
+            result,
+            Err(Error::AllocationLimitExceeded { limit: 100_000 })
+        ));
+
+        let options = EvaluationOptions::default().with_allocation_limit(10_000_000);
+        assert!(evaluate_expression_with_options(&expression, &batch, None, &options).is_ok());
+    }
+
+    #[test]
+    fn test_comparison_with_null() {
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, false)]);
+        let values = Int32Array::from(vec![1, 2, 3]);
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(values)]).unwrap();
+        let column = Expression::column("a");
+        let options = EvaluationOptions::default();
+
+        let expected = BooleanArray::from(vec![None, None, None]);
+        let null = || Expression::literal(Scalar::Null(DeltaDataTypes::INTEGER));
+        for expression in [
+            column.clone().eq(null()),
+            column.clone().ne(null()),
+            column.clone().lt(null()),
+            null().gt_eq(column.clone()),
+            // the type of the NULL doesn't matter
+            column
+                .clone()
+                .eq(Expression::literal(Scalar::Null(DeltaDataTypes::STRING))),
+        ] {
+            let result =
+                evaluate_expression_with_options(&expression, &batch, None, &options).unwrap();
+            assert_eq!(result.as_ref(), &expected, "{expression}");
+        }
+
+        // unlike IS DISTINCT FROM
+        let expression = column.clone().distinct(null());
+        let result = evaluate_expression_with_options(&expression, &batch, None, &options).unwrap();
+        assert_eq!(result.as_ref(), &BooleanArray::from(vec![true; 3]));
+    }
+
+    #[test]
+    fn test_evaluate_with_field() {
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, false)]);
+        let values = Int32Array::from(vec![1, 2, 3]);
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(values)]).unwrap();
+        let input_schema = Arc::new(crate::schema::StructType::new(vec![
+            crate::schema::StructField::new("a", DeltaDataTypes::INTEGER, false),
+        ]));
+        let handler = ArrowExpressionHandler::default();
+        let evaluator = |expression: Expression| {
+            handler.get_default_evaluator(input_schema.clone(), expression, DeltaDataTypes::INTEGER)
+        };
+
+        let expression = (Expression::column("a") + Expression::literal(1)).alias("x");
+        let (result, field) = evaluator(expression).evaluate_with_field(&batch).unwrap();
+        assert_eq!(result.as_ref(), &Int32Array::from(vec![2, 3, 4]));
+        assert_eq!(field, Field::new("x", DataType::Int32, false));
+
+        let expression =
+            Expression::column("a") + Expression::literal(Scalar::Null(DeltaDataTypes::INTEGER));
+        let (_, field) = evaluator(expression).evaluate_with_field(&batch).unwrap();
+        assert_eq!(field, Field::new("output", DataType::Int32, true));
+    }
+
+    #[test]
+    fn test_evaluate_predicate() {
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, true)]);
+        let values = Int32Array::from(vec![Some(1), None, Some(3)]);
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(values)]).unwrap();
+        let predicate = Expression::column("a").gt(Expression::literal(2));
+
+        let handler = ArrowExpressionHandler::default();
+        let result = handler.evaluate_predicate(&batch, &predicate).unwrap();
+        let expected = BooleanArray::from(vec![Some(false), None, Some(true)]);
+        assert_eq!(result.result(), &expected);
+        assert_eq!(result.result().null_count(), 1);
+        assert_eq!(
+            result.matching(),
+            BooleanArray::from(vec![false, false, true])
+        );
+        assert_eq!(
+            result.possibly_matching(),
+            BooleanArray::from(vec![false, true, true])
+        );
+    }
+
+    #[test]
+    fn test_table_qualifier() {
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, false)]);
+        let values = Int32Array::from(vec![1, 2, 3]);
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(values)]).unwrap();
+        let expression = Expression::column("t.a").gt(Expression::literal(2));
+
+        let options = EvaluationOptions::default();
+        assert!(evaluate_expression_with_options(&expression, &batch, None, &options).is_err());
+
+        let options = EvaluationOptions::default().with_table_qualifier("t");
+        let result = evaluate_expression_with_options(&expression, &batch, None, &options).unwrap();
+        let expected = BooleanArray::from(vec![false, false, true]);
+        assert_eq!(result.as_ref(), &expected);
+        let handler = ArrowExpressionHandler::new(options.clone());
+        let results = handler
+            .evaluate_all(&batch, std::slice::from_ref(&expression))
+            .unwrap();
+        assert_eq!(results[0].as_ref(), &expected);
+
+        // a struct column named like the table takes precedence
+        let struct_field = Field::new("a", DataType::Int32, false);
+        let struct_array = StructArray::from(vec![(
+            Arc::new(struct_field.clone()),
+            Arc::new(Int32Array::from(vec![3, 3, 3])) as ArrayRef,
+        )]);
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("t", DataType::Struct(vec![struct_field].into()), false),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 3])),
+                Arc::new(struct_array),
+            ],
+        )
+        .unwrap();
+        let result = evaluate_expression_with_options(&expression, &batch, None, &options).unwrap();
+        assert_eq!(result.as_ref(), &BooleanArray::from(vec![true, true, true]));
+    }
+
+    #[test]
+    fn test_evaluate_with_null_count() {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Int32, true),
+        ]);
+        let a = Int32Array::from(vec![Some(1), None, Some(3), Some(4)]);
+        let b = Int32Array::from(vec![Some(1), Some(2), None, None]);
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(a), Arc::new(b)]).unwrap();
+        let input_schema = Arc::new(crate::schema::StructType::new(vec![
+            crate::schema::StructField::new("a", DeltaDataTypes::INTEGER, true),
+            crate::schema::StructField::new("b", DeltaDataTypes::INTEGER, true),
+        ]));
+        let evaluator = ArrowExpressionHandler::default().get_default_evaluator(
+            input_schema,
+            Expression::column("a") + Expression::column("b"),
+            DeltaDataTypes::INTEGER,
+        );
+
+        let (result, null_count) = evaluator.evaluate_with_null_count(&batch).unwrap();
+        let expected = Int32Array::from(vec![Some(2), None, None, None]);
+        assert_eq!(result.as_ref(), &expected);
+        assert_eq!(null_count, 3);
+    }
+
+    #[test]
+    fn test_float_arithmetic_int_comparison() {
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, false)]);
+        let values = Int32Array::from(vec![1, 2, 3, 4]);
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(values)]).unwrap();
+        let options = EvaluationOptions::default();
+
+        // dividing two integers is integer division, so `a / 2` is `[0, 1, 1, 2]` and there is no
+        // float to coerce the literal to
+        let expression = (Expression::column("a") / Expression::literal(2))
+            .gt(Expression::literal(Scalar::Integer(1)));
+        let result = evaluate_expression(&expression, &batch, None).unwrap();
+        let expected = BooleanArray::from(vec![false, false, false, true]);
+        assert_eq!(result.as_ref(), &expected);
+
+        // the integer column is coerced to a float for the division, and the integer literal is
+        // then coerced to a float for the comparison with its result
+        let quotient = Expression::column("a") / Expression::literal(2.0);
+        let result = evaluate_expression_with_options(&quotient, &batch, None, &options).unwrap();
+        assert_eq!(
+            result.as_ref(),
+            &Float64Array::from(vec![0.5, 1.0, 1.5, 2.0])
+        );
+
+        let expression = quotient.gt(Expression::literal(Scalar::Integer(1)));
+        let result = evaluate_expression_with_options(&expression, &batch, None, &options).unwrap();
+        let expected = BooleanArray::from(vec![false, false, true, true]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_like_on_dictionary() {
+        let values: ArrayRef = Arc::new(StringArray::from(vec!["apple", "banana", "avocado"]));
+        let dictionary_type =
+            DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8));
+        let schema = Arc::new(Schema::new(vec![Field::new("s", dictionary_type, true)]));
+        let batch = |keys: Vec<Option<i32>>| {
+            let keys = Int32Array::from(keys);
+            let strings = DictionaryArray::new(keys, values.clone());
+            RecordBatch::try_new(schema.clone(), vec![Arc::new(strings)]).unwrap()
+        };
+        let evaluator = DefaultExpressionEvaluator {
+            input_schema: Arc::new(crate::schema::StructType::new(vec![
+                crate::schema::StructField::new("s", DeltaDataTypes::STRING, true),
+            ])),
+            expression: Box::new(Expression::column("s").like(Expression::literal("a%"))),
+            output_type: DeltaDataTypes::BOOLEAN,
+            options: EvaluationOptions::default(),
+            like_cache: LikeCache::default(),
+        };
+
+        // the batches share their dictionary, and give the same results as without the cache
+        for keys in [
+            vec![Some(0), Some(1), None],
+            vec![Some(2), Some(2), Some(1)],
+        ] {
+            let batch = batch(keys);
+            let (cached, _) = evaluator.evaluate_with_null_count(&batch).unwrap();
+            let naive = evaluate_expression(&evaluator.expression, &batch, None).unwrap();
+            assert_eq!(cached.as_ref(), naive.as_ref());
+        }
+    }
+
+    #[test]
+    fn test_normalize_float_zeros() {
+        let floats: ArrayRef = Arc::new(Float64Array::from(vec![1.0, 0.0, f64::NAN]));
+        assert!(Arc::ptr_eq(&normalize_float_zeros(floats.clone()), &floats));
+
+        let floats: ArrayRef = Arc::new(Float32Array::from(vec![1.0, -0.0]));
+        let normalized = normalize_float_zeros(floats.clone());
+        assert!(!Arc::ptr_eq(&normalized, &floats));
+        let normalized = normalized.as_primitive::<Float32Type>();
+        assert!(normalized.value(1).is_sign_positive());
+    }
 }
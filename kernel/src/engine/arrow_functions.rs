@@ -0,0 +1,819 @@
+//! Arrow kernels for the [`ScalarFunction`]s supported by the arrow expression handler.
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use arrow_arith::boolean::is_not_null;
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Float64Type, Int32Type};
+use arrow_array::{
+    Array, ArrayRef, BooleanArray, Decimal128Array, Int32Array, Int64Array, Scalar, StringArray,
+};
+use arrow_cast::cast;
+use arrow_schema::DataType;
+use arrow_select::take::take;
+use arrow_select::zip::zip;
+use arrow_string::like::like;
+use itertools::{izip, Itertools};
+
+use crate::error::{DeltaResult, Error};
+use crate::expressions::ScalarFunction;
+
+/// Evaluate `func` over its already evaluated arguments, which must all have the same length.
+pub(crate) fn evaluate_function(func: &ScalarFunction, args: &[ArrayRef]) -> DeltaResult<ArrayRef> {
+    match func {
+        ScalarFunction::SplitPart => {
+            let [strings, delimiters, parts] = expect_args(func, args)?;
+            split_part(
+                as_string(func, strings)?,
+                as_string(func, delimiters)?,
+                as_int(func, parts)?,
+            )
+        }
+        ScalarFunction::Replace => {
+            let [strings, froms, tos] = expect_args(func, args)?;
+            Ok(replace(
+                as_string(func, strings)?,
+                as_string(func, froms)?,
+                as_string(func, tos)?,
+            ))
+        }
+        ScalarFunction::Reverse => {
+            let [strings] = expect_args(func, args)?;
+            Ok(map_strings(as_string(func, strings)?, |s| {
+                s.chars().rev().collect()
+            }))
+        }
+        ScalarFunction::Initcap => {
+            let [strings] = expect_args(func, args)?;
+            Ok(map_strings(as_string(func, strings)?, initcap))
+        }
+        ScalarFunction::Like => {
+            let [strings, patterns] = expect_args(func, args)?;
+            Ok(Arc::new(like(strings, patterns)?))
+        }
+        ScalarFunction::TypeOf => {
+            let [arr] = expect_args(func, args)?;
+            let type_name = arr.data_type().to_string();
+            Ok(Arc::new(StringArray::from(vec![type_name; arr.len()])))
+        }
+        ScalarFunction::Coalesce => coalesce(func, args),
+        ScalarFunction::Nvl => {
+            let args: &[ArrayRef; 2] = expect_args(func, args)?;
+            coalesce(func, args)
+        }
+        ScalarFunction::Nvl2 => {
+            let [exprs, if_not_null, if_null] = expect_args(func, args)?;
+            expect_same_types(func, [if_not_null, if_null])?;
+            Ok(zip(&is_not_null(exprs)?, if_not_null, if_null)?)
+        }
+        ScalarFunction::MonotonicallyIncreasingId => {
+            let [partitions] = expect_args(func, args)?;
+            monotonically_increasing_id(as_int(func, partitions)?)
+        }
+        ScalarFunction::Md5 | ScalarFunction::Sha2 => hash_function(func, args),
+        ScalarFunction::FormatNumber => {
+            let [numbers, decimals] = expect_args(func, args)?;
+            format_number(func, numbers, as_int(func, decimals)?)
+        }
+    }
+}
+
+/// The matches of LIKE patterns against the values of the dictionary of a dictionary-encoded string
+/// column, kept across batches. Batches read from the same column chunk typically share their
+/// dictionary, and then each distinct value is matched once rather than once per batch (or, as
+/// arrow would do without the cache, once per row if the pattern is an array rather than a scalar).
+///
+/// Only the matches against the most recent dictionary are kept for each pattern. Dictionaries are
+/// told apart by the identity of their values array, which the cache keeps alive so that it can't
+/// be mistaken for a new dictionary allocated in its place.
+///
+/// The cache is shared by every thread using the same evaluator, behind a single mutex. The lock is
+/// only held to look up or insert an entry, never while matching, but threads evaluating batches
+/// concurrently still contend on it once per dictionary-encoded batch.
+#[derive(Debug, Default)]
+pub(crate) struct LikeCache {
+    matches: Mutex<HashMap<String, (ArrayRef, BooleanArray)>>,
+}
+
+impl LikeCache {
+    /// Whether each of `strings` matches the LIKE `pattern`, reusing the matches against the
+    /// dictionary of `strings` if it is dictionary-encoded.
+    pub(crate) fn like(&self, strings: &ArrayRef, pattern: &str) -> DeltaResult<ArrayRef> {
+        let Some(dictionary) = strings.as_any_dictionary_opt() else {
+            return Ok(Arc::new(like(
+                strings,
+                &Scalar::new(StringArray::from(vec![pattern])),
+            )?));
+        };
+        let values = dictionary.values();
+        let cached = self
+            .matches
+            .lock()
+            // the cache is only ever replaced as a whole, so it is consistent even after a panic
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(pattern)
+            .filter(|(cached_values, _)| Arc::ptr_eq(cached_values, values))
+            .map(|(_, matches)| matches.clone());
+        let matches = match cached {
+            Some(matches) => matches,
+            None => {
+                let matches = like(values, &Scalar::new(StringArray::from(vec![pattern])))?;
+                self.matches
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .insert(pattern.to_string(), (values.clone(), matches.clone()));
+                matches
+            }
+        };
+        Ok(take(&matches, dictionary.keys(), None)?)
+    }
+}
+
+fn expect_args<'a, const N: usize>(
+    func: &ScalarFunction,
+    args: &'a [ArrayRef],
+) -> DeltaResult<&'a [ArrayRef; N]> {
+    args.try_into().map_err(|_| {
+        Error::invalid_expression(format!("{func} expects {N} arguments, got {}", args.len()))
+    })
+}
+
+fn as_string<'a>(func: &ScalarFunction, arr: &'a ArrayRef) -> DeltaResult<&'a StringArray> {
+    arr.as_string_opt::<i32>().ok_or_else(|| {
+        Error::invalid_expression(format!(
+            "{func} expects a string argument, got {}",
+            arr.data_type()
+        ))
+    })
+}
+
+fn as_int<'a>(func: &ScalarFunction, arr: &'a ArrayRef) -> DeltaResult<&'a Int32Array> {
+    arr.as_primitive_opt::<Int32Type>().ok_or_else(|| {
+        Error::invalid_expression(format!(
+            "{func} expects an integer argument, got {}",
+            arr.data_type()
+        ))
+    })
+}
+
+/// Follows Spark semantics: fields are 1-based and a negative index counts from the end, an index
+/// that is out of range yields an empty string, and an empty delimiter doesn't split the string.
+/// A zero index is an error.
+fn split_part(
+    strings: &StringArray,
+    delimiters: &StringArray,
+    parts: &Int32Array,
+) -> DeltaResult<ArrayRef> {
+    let result: StringArray = izip!(strings, delimiters, parts)
+        .map(|row| match row {
+            (Some(string), Some(delimiter), Some(part)) => {
+                split_part_value(string, delimiter, part).map(Some)
+            }
+            _ => Ok(None),
+        })
+        .collect::<DeltaResult<_>>()?;
+    Ok(Arc::new(result))
+}
+
+fn split_part_value<'a>(string: &'a str, delimiter: &str, part: i32) -> DeltaResult<&'a str> {
+    let index = match part {
+        0 => {
+            return Err(Error::invalid_expression(
+                "SPLIT_PART field index must not be zero",
+            ))
+        }
+        part => part.unsigned_abs() as usize - 1,
+    };
+    let field = match (delimiter.is_empty(), part > 0) {
+        (true, _) => (index == 0).then_some(string),
+        (false, true) => string.split(delimiter).nth(index),
+        (false, false) => string.rsplit(delimiter).nth(index),
+    };
+    Ok(field.unwrap_or(""))
+}
+
+/// An empty `from` leaves the string unchanged, rather than inserting `to` before every character
+/// like [`str::replace`] would.
+fn replace(strings: &StringArray, froms: &StringArray, tos: &StringArray) -> ArrayRef {
+    let result: StringArray = izip!(strings, froms, tos)
+        .map(|row| match row {
+            (Some(string), Some(""), Some(_)) => Some(string.to_string()),
+            (Some(string), Some(from), Some(to)) => Some(string.replace(from, to)),
+            _ => None,
+        })
+        .collect();
+    Arc::new(result)
+}
+
+fn map_strings(strings: &StringArray, f: impl Fn(&str) -> String) -> ArrayRef {
+    let result: StringArray = strings.iter().map(|s| s.map(&f)).collect();
+    Arc::new(result)
+}
+
+fn initcap(string: &str) -> String {
+    let mut result = String::with_capacity(string.len());
+    let mut word_start = true;
+    for c in string.chars() {
+        if word_start {
+            result.extend(c.to_uppercase());
+        } else {
+            result.extend(c.to_lowercase());
+        }
+        word_start = c.is_whitespace();
+    }
+    result
+}
+
+/// Works backwards from the last argument, replacing the result with each earlier argument wherever
+/// that argument is not null.
+fn coalesce(func: &ScalarFunction, args: &[ArrayRef]) -> DeltaResult<ArrayRef> {
+    let (last, rest) = args.split_last().ok_or_else(|| {
+        Error::invalid_expression(format!("{func} expects at least one argument, got 0"))
+    })?;
+    expect_same_types(func, args)?;
+    rest.iter().rev().try_fold(last.clone(), |result, arg| {
+        Ok(zip(&is_not_null(arg)?, arg, &result)?)
+    })
+}
+
+/// Check that `args`, of which any one may be the result, all have the same type, since they are
+/// not converted to a common type.
+fn expect_same_types<'a>(
+    func: &ScalarFunction,
+    args: impl IntoIterator<Item = &'a ArrayRef>,
+) -> DeltaResult<()> {
+    let types: Vec<_> = args.into_iter().map(|arg| arg.data_type()).collect();
+    if types.iter().all_equal() {
+        return Ok(());
+    }
+    Err(Error::invalid_expression(format!(
+        "{func} expects arguments of the same type, got {}",
+        types.iter().join(", ")
+    )))
+}
+
+#[cfg(feature = "hash-functions")]
+fn hash_function(func: &ScalarFunction, args: &[ArrayRef]) -> DeltaResult<ArrayRef> {
+    use arrow_array::new_null_array;
+    match func {
+        ScalarFunction::Md5 => {
+            let [values] = expect_args(func, args)?;
+            hash(func, values, |value| Some(md5_value(value)))
+        }
+        _ => {
+            let [values, bits] = expect_args(func, args)?;
+            let Some(bits) = single_int_arg(func, bits)? else {
+                return Ok(new_null_array(&DataType::Utf8, values.len()));
+            };
+            hash(func, values, |value| sha2_value(value, bits))
+        }
+    }
+}
+
+#[cfg(not(feature = "hash-functions"))]
+fn hash_function(func: &ScalarFunction, _args: &[ArrayRef]) -> DeltaResult<ArrayRef> {
+    Err(Error::invalid_expression(format!(
+        "{func} requires the `hash-functions` feature"
+    )))
+}
+
+/// Hash each string or binary value of `values`, keeping nulls as they are.
+#[cfg(feature = "hash-functions")]
+fn hash(
+    func: &ScalarFunction,
+    values: &ArrayRef,
+    hash_value: impl Fn(&[u8]) -> Option<String>,
+) -> DeltaResult<ArrayRef> {
+    let result: StringArray = match values.data_type() {
+        DataType::Utf8 => values
+            .as_string::<i32>()
+            .iter()
+            .map(|value| value.and_then(|v| hash_value(v.as_bytes())))
+            .collect(),
+        DataType::Binary => values
+            .as_binary::<i32>()
+            .iter()
+            .map(|value| value.and_then(&hash_value))
+            .collect(),
+        data_type => {
+            return Err(Error::invalid_expression(format!(
+                "{func} expects a string or binary argument, got {data_type}"
+            )))
+        }
+    };
+    Ok(Arc::new(result))
+}
+
+/// The hex digest of `value` with MD5.
+#[cfg(feature = "hash-functions")]
+fn md5_value(value: &[u8]) -> String {
+    use md5::{Digest, Md5};
+    to_hex(&Md5::digest(value))
+}
+
+/// The hex digest of `value` with the SHA-2 function of the given number of bits (where 0 means
+/// 256), or `None` if no such SHA-2 function exists.
+#[cfg(feature = "hash-functions")]
+fn sha2_value(value: &[u8], bits: i32) -> Option<String> {
+    use sha2::{Digest, Sha224, Sha256, Sha384, Sha512};
+    let digest = match bits {
+        224 => Sha224::digest(value).to_vec(),
+        0 | 256 => Sha256::digest(value).to_vec(),
+        384 => Sha384::digest(value).to_vec(),
+        512 => Sha512::digest(value).to_vec(),
+        _ => return None,
+    };
+    Some(to_hex(&digest))
+}
+
+#[cfg(feature = "hash-functions")]
+fn to_hex(digest: &[u8]) -> String {
+    digest.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// An integer argument that must have the same value for every row, like the number of bits of a
+/// hash, or `None` if it is null.
+#[cfg(feature = "hash-functions")]
+fn single_int_arg(func: &ScalarFunction, arr: &ArrayRef) -> DeltaResult<Option<i32>> {
+    let arr = as_int(func, arr)?;
+    let Some(first) = arr.iter().next() else {
+        return Ok(None);
+    };
+    if arr.iter().any(|value| value != first) {
+        return Err(Error::invalid_expression(format!(
+            "{func} expects the same value of its integer argument for every row"
+        )));
+    }
+    Ok(first)
+}
+
+/// The number of low bits of an id that hold the offset of the row within its batch.
+const ROW_OFFSET_BITS: u32 = 33;
+/// The largest partition index that fits in the high bits of an id without reaching its sign bit.
+const MAX_PARTITION: i32 = (1 << (i64::BITS - 1 - ROW_OFFSET_BITS)) - 1;
+
+fn monotonically_increasing_id(partitions: &Int32Array) -> DeltaResult<ArrayRef> {
+    if partitions.len() as u64 > 1 << ROW_OFFSET_BITS {
+        return Err(Error::invalid_expression(format!(
+            "MONOTONICALLY_INCREASING_ID supports at most 2^{ROW_OFFSET_BITS} rows per batch"
+        )));
+    }
+    let result: Int64Array = partitions
+        .iter()
+        .enumerate()
+        .map(|(offset, partition)| match partition {
+            Some(partition) if !(0..=MAX_PARTITION).contains(&partition) => {
+                Err(Error::invalid_expression(format!(
+                    "MONOTONICALLY_INCREASING_ID partition index must be between 0 and \
+                    {MAX_PARTITION}, got {partition}"
+                )))
+            }
+            Some(partition) => Ok(Some(
+                ((partition as i64) << ROW_OFFSET_BITS) | offset as i64,
+            )),
+            None => Ok(None),
+        })
+        .collect::<DeltaResult<_>>()?;
+    Ok(Arc::new(result))
+}
+
+/// The most decimal places `FORMAT_NUMBER` can format a number with.
+const MAX_FORMAT_DECIMALS: i32 = u16::MAX as i32;
+
+/// Floats are formatted from their (rounded) binary value, and integers and decimals from their
+/// exact value, rounding half to even, so that e.g. large longs don't lose their last digits.
+fn format_number(
+    func: &ScalarFunction,
+    numbers: &ArrayRef,
+    decimals: &Int32Array,
+) -> DeltaResult<ArrayRef> {
+    if let Some(decimals) = decimals.iter().flatten().find(|d| *d > MAX_FORMAT_DECIMALS) {
+        return Err(Error::invalid_expression(format!(
+            "{func} supports at most {MAX_FORMAT_DECIMALS} decimal places, got {decimals}"
+        )));
+    }
+    let decimals = decimals
+        .iter()
+        .map(|d| d.and_then(|d| usize::try_from(d).ok()));
+    let result: StringArray = match numbers.data_type() {
+        DataType::Float16 | DataType::Float32 | DataType::Float64 => {
+            let numbers = cast(numbers, &DataType::Float64)?;
+            let numbers = numbers.as_primitive::<Float64Type>();
+            numbers
+                .iter()
+                .zip(decimals)
+                .map(|row| match row {
+                    (Some(number), Some(decimals)) => Some(format_float(number, decimals)),
+                    _ => None,
+                })
+                .collect()
+        }
+        DataType::Decimal128(_, scale) if *scale >= 0 => {
+            format_decimals(numbers.as_primitive(), *scale as u32, decimals)
+        }
+        data_type if data_type.is_numeric() => {
+            // Integers (and decimals of negative scale) convert to decimals exactly
+            let numbers = cast(numbers, &DataType::Decimal128(38, 0))?;
+            format_decimals(numbers.as_primitive(), 0, decimals)
+        }
+        data_type => {
+            return Err(Error::invalid_expression(format!(
+                "{func} expects a numeric argument, got {data_type}"
+            )))
+        }
+    };
+    Ok(Arc::new(result))
+}
+
+fn format_decimals(
+    numbers: &Decimal128Array,
+    scale: u32,
+    decimals: impl Iterator<Item = Option<usize>>,
+) -> StringArray {
+    numbers
+        .iter()
+        .zip(decimals)
+        .map(|row| match row {
+            (Some(number), Some(decimals)) => Some(format_decimal(number, scale, decimals)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn format_float(number: f64, decimals: usize) -> String {
+    group_thousands(
+        number.is_sign_negative(),
+        &format!("{:.*}", decimals, number.abs()),
+    )
+}
+
+/// Format the decimal of the given `value` and `scale` with `decimals` decimal places.
+fn format_decimal(value: i128, scale: u32, decimals: usize) -> String {
+    let abs = value.unsigned_abs();
+    // The scale of a `Decimal128` is at most 38, so any divisor fits in a `u128`
+    let (digits, digits_scale, padding) = match u32::try_from(decimals) {
+        Ok(decimals) if decimals < scale => {
+            let divisor = 10_u128.pow(scale - decimals);
+            let (quotient, remainder) = (abs / divisor, abs % divisor);
+            let half = divisor / 2;
+            let round_up = remainder > half || (remainder == half && quotient % 2 == 1);
+            (quotient + round_up as u128, decimals as usize, 0)
+        }
+        _ => (abs, scale as usize, decimals - scale as usize),
+    };
+    let digits = format!("{digits:0>width$}", width = digits_scale + 1);
+    let (integer, fraction) = digits.split_at(digits.len() - digits_scale);
+    let mut formatted = integer.to_string();
+    if decimals > 0 {
+        formatted.push('.');
+        formatted.push_str(fraction);
+        formatted.extend(std::iter::repeat_n('0', padding));
+    }
+    group_thousands(value < 0, &formatted)
+}
+
+/// Insert commas between the thousands of the integer part of the non-negative number
+/// `formatted`, and a minus sign before it if `negative` and it is not all zeros.
+fn group_thousands(negative: bool, formatted: &str) -> String {
+    let (integer, fraction) = match formatted.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (formatted, None),
+    };
+    let mut result = String::with_capacity(formatted.len() + integer.len() / 3 + 1);
+    if negative && formatted.bytes().any(|b| matches!(b, b'1'..=b'9')) {
+        result.push('-');
+    }
+    // NaN and infinity have no digits to group
+    let is_digits = integer.bytes().all(|b| b.is_ascii_digit());
+    for (i, c) in integer.chars().enumerate() {
+        if is_digits && i > 0 && (integer.len() - i) % 3 == 0 {
+            result.push(',');
+        }
+        result.push(c);
+    }
+    if let Some(fraction) = fraction {
+        result.push('.');
+        result.push_str(fraction);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::Scalar;
+    use arrow_array::{DictionaryArray, Float64Array};
+
+    fn split_part_of(string: Scalar, part: i32) -> DeltaResult<ArrayRef> {
+        let args = [string, Scalar::from("/"), Scalar::from(part)]
+            .iter()
+            .map(|arg| arg.to_array(1))
+            .collect::<DeltaResult<Vec<_>>>()?;
+        evaluate_function(&ScalarFunction::SplitPart, &args)
+    }
+
+    #[test]
+    fn test_split_part() {
+        let string = Scalar::from("a/b/c");
+        let cases = [(1, "a"), (2, "b"), (3, "c"), (4, ""), (-1, "c"), (-4, "")];
+        for (part, expected) in cases {
+            let result = split_part_of(string.clone(), part).unwrap();
+            assert_eq!(result.as_string::<i32>().value(0), expected, "part {part}");
+        }
+
+        let result = split_part_of(Scalar::Null(crate::schema::DataType::STRING), 1).unwrap();
+        assert!(result.is_null(0));
+
+        let result = split_part_of(string, 0);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_split_part_bad_args() {
+        let args = [Scalar::from("a/b").to_array(1).unwrap()];
+        let result = evaluate_function(&ScalarFunction::SplitPart, &args);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Invalid expression evaluation: SPLIT_PART expects 3 arguments, got 1"
+        );
+    }
+
+    #[test]
+    fn test_type_of() {
+        let args: [ArrayRef; 1] = [Arc::new(Int32Array::from(vec![Some(1), None, Some(3)]))];
+        let result = evaluate_function(&ScalarFunction::TypeOf, &args).unwrap();
+        let expected = StringArray::from(vec!["Int32"; 3]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_replace() {
+        let strings = StringArray::from(vec![Some("a-b-c"), Some("abc"), None]);
+        let replace_all = |from: &str, to: &str| {
+            let args: [ArrayRef; 3] = [
+                Arc::new(strings.clone()),
+                Scalar::from(from).to_array(3).unwrap(),
+                Scalar::from(to).to_array(3).unwrap(),
+            ];
+            evaluate_function(&ScalarFunction::Replace, &args).unwrap()
+        };
+
+        let expected = StringArray::from(vec![Some("a+b+c"), Some("abc"), None]);
+        assert_eq!(replace_all("-", "+").as_ref(), &expected);
+
+        let expected = StringArray::from(vec![Some("a-b-c"), Some("abc"), None]);
+        assert_eq!(replace_all("x", "+").as_ref(), &expected);
+        assert_eq!(replace_all("", "+").as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_coalesce() {
+        let args: [ArrayRef; 3] = [
+            Arc::new(Int32Array::from(vec![Some(1), None, None, None])),
+            Arc::new(Int32Array::from(vec![Some(2), Some(2), None, None])),
+            Arc::new(Int32Array::from(vec![Some(3), Some(3), Some(3), None])),
+        ];
+        let result = evaluate_function(&ScalarFunction::Coalesce, &args).unwrap();
+        let expected = Int32Array::from(vec![Some(1), Some(2), Some(3), None]);
+        assert_eq!(result.as_ref(), &expected);
+
+        let result = evaluate_function(&ScalarFunction::Coalesce, &[]);
+        assert!(result.is_err());
+
+        let args = [args[0].clone(), Scalar::from(0i64).to_array(4).unwrap()];
+        let error = evaluate_function(&ScalarFunction::Coalesce, &args).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid expression evaluation: COALESCE expects arguments of the same type, got \
+            Int32, Int64"
+        );
+    }
+
+    #[test]
+    fn test_format_number() {
+        let numbers = Float64Array::from(vec![
+            Some(1234567.891),
+            Some(-1234.5),
+            Some(0.004),
+            Some(-0.004),
+            Some(999.999),
+            None,
+        ]);
+        let args: [ArrayRef; 2] = [Arc::new(numbers), Scalar::from(2).to_array(6).unwrap()];
+        let result = evaluate_function(&ScalarFunction::FormatNumber, &args).unwrap();
+        let expected = StringArray::from(vec![
+            Some("1,234,567.89"),
+            Some("-1,234.50"),
+            Some("0.00"),
+            Some("0.00"),
+            Some("1,000.00"),
+            None,
+        ]);
+        assert_eq!(result.as_ref(), &expected);
+
+        let args: [ArrayRef; 2] = [
+            Arc::new(Int32Array::from(vec![1234, 12, -123456])),
+            Arc::new(Int32Array::from(vec![Some(0), Some(1), Some(-1)])),
+        ];
+        let result = evaluate_function(&ScalarFunction::FormatNumber, &args).unwrap();
+        let expected = StringArray::from(vec![Some("1,234"), Some("12.0"), None]);
+        assert_eq!(result.as_ref(), &expected);
+
+        let args = [
+            Scalar::from("x").to_array(1).unwrap(),
+            Scalar::from(2).to_array(1).unwrap(),
+        ];
+        assert!(evaluate_function(&ScalarFunction::FormatNumber, &args).is_err());
+
+        // too many decimal places are rejected rather than formatted
+        let args = [
+            Scalar::from(1.5).to_array(1).unwrap(),
+            Scalar::from(70000).to_array(1).unwrap(),
+        ];
+        assert!(evaluate_function(&ScalarFunction::FormatNumber, &args).is_err());
+    }
+
+    #[test]
+    fn test_format_number_exact() {
+        // longs and decimals are formatted from their exact values, not from doubles
+        let args: [ArrayRef; 2] = [
+            Arc::new(Int64Array::from(vec![9007199254740993, -9007199254740993])),
+            Arc::new(Int32Array::from(vec![0, 2])),
+        ];
+        let result = evaluate_function(&ScalarFunction::FormatNumber, &args).unwrap();
+        let expected =
+            StringArray::from(vec!["9,007,199,254,740,993", "-9,007,199,254,740,993.00"]);
+        assert_eq!(result.as_ref(), &expected);
+
+        let decimals = Decimal128Array::from(vec![1234567, -2500, 125, 135, -4])
+            .with_precision_and_scale(10, 3)
+            .unwrap();
+        let format = |places: i32| {
+            let args = [
+                Arc::new(decimals.clone()) as ArrayRef,
+                Scalar::from(places).to_array(5).unwrap(),
+            ];
+            evaluate_function(&ScalarFunction::FormatNumber, &args).unwrap()
+        };
+        // rounding half to even
+        let expected = StringArray::from(vec!["1,234.57", "-2.50", "0.12", "0.14", "0.00"]);
+        assert_eq!(format(2).as_ref(), &expected);
+        let expected = StringArray::from(vec!["1,235", "-2", "0", "0", "0"]);
+        assert_eq!(format(0).as_ref(), &expected);
+        let expected = StringArray::from(vec![
+            "1,234.56700",
+            "-2.50000",
+            "0.12500",
+            "0.13500",
+            "-0.00400",
+        ]);
+        assert_eq!(format(5).as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_nvl() {
+        let exprs: ArrayRef = Arc::new(Int32Array::from(vec![Some(1), None, Some(3), None]));
+        let defaults: ArrayRef = Arc::new(Int32Array::from(vec![Some(10), Some(20), None, None]));
+        let args = [exprs.clone(), defaults.clone()];
+        let result = evaluate_function(&ScalarFunction::Nvl, &args).unwrap();
+        let expected = Int32Array::from(vec![Some(1), Some(20), Some(3), None]);
+        assert_eq!(result.as_ref(), &expected);
+
+        let args = [exprs.clone(), defaults.clone(), exprs.clone()];
+        assert!(evaluate_function(&ScalarFunction::Nvl, &args).is_err());
+        let args = [exprs.clone(), Scalar::from(0i64).to_array(4).unwrap()];
+        assert!(evaluate_function(&ScalarFunction::Nvl, &args).is_err());
+
+        let args = [
+            exprs.clone(),
+            defaults,
+            Scalar::from(0).to_array(4).unwrap(),
+        ];
+        let result = evaluate_function(&ScalarFunction::Nvl2, &args).unwrap();
+        let expected = Int32Array::from(vec![Some(10), Some(0), None, Some(0)]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_monotonically_increasing_id() {
+        let args = [Scalar::from(3).to_array(2).unwrap()];
+        let func = ScalarFunction::MonotonicallyIncreasingId;
+        let result = evaluate_function(&func, &args).unwrap();
+        let expected = Int64Array::from(vec![25769803776, 25769803777]);
+        assert_eq!(result.as_ref(), &expected);
+        assert_eq!(evaluate_function(&func, &args).unwrap().as_ref(), &expected);
+
+        let args: [ArrayRef; 1] = [Arc::new(Int32Array::from(vec![Some(0), None]))];
+        let result = evaluate_function(&func, &args).unwrap();
+        assert_eq!(result.as_ref(), &Int64Array::from(vec![Some(0), None]));
+
+        let args = [Scalar::from(-1).to_array(1).unwrap()];
+        assert!(evaluate_function(&func, &args).is_err());
+
+        let args = [Scalar::from((1 << 30) - 1).to_array(1).unwrap()];
+        let result = evaluate_function(&func, &args).unwrap();
+        assert_eq!(
+            result.as_ref(),
+            &Int64Array::from(vec![((1 << 30) - 1) << 33])
+        );
+        let args = [Scalar::from(1 << 30).to_array(1).unwrap()];
+        assert!(evaluate_function(&func, &args).is_err());
+    }
+
+    #[cfg(feature = "hash-functions")]
+    #[test]
+    fn test_hash_functions() {
+        let strings: ArrayRef = Arc::new(StringArray::from(vec![Some("Spark"), Some(""), None]));
+        let result =
+            evaluate_function(&ScalarFunction::Md5, std::slice::from_ref(&strings)).unwrap();
+        let expected = StringArray::from(vec![
+            Some("8cde774d6f7333752ed72cacddb05126"),
+            Some("d41d8cd98f00b204e9800998ecf8427e"),
+            None,
+        ]);
+        assert_eq!(result.as_ref(), &expected);
+
+        let sha2 = |bits: i32| {
+            let args = [strings.clone(), Scalar::from(bits).to_array(3).unwrap()];
+            evaluate_function(&ScalarFunction::Sha2, &args).unwrap()
+        };
+        let expected = StringArray::from(vec![
+            Some("529bc3b07127ecb7e53a4dcf1991d9152c24537d919178022b2c42657f79a26b"),
+            Some("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"),
+            None,
+        ]);
+        assert_eq!(sha2(256).as_ref(), &expected);
+        assert_eq!(sha2(0).as_ref(), &expected);
+        assert_eq!(sha2(1).as_ref(), &StringArray::from(vec![None::<&str>; 3]));
+        // 128 bits is MD5, which is not a SHA-2 function
+        assert_eq!(
+            sha2(128).as_ref(),
+            &StringArray::from(vec![None::<&str>; 3])
+        );
+
+        let binary: ArrayRef = Arc::new(arrow_array::BinaryArray::from(vec![b"Spark".as_ref()]));
+        let result = evaluate_function(&ScalarFunction::Md5, &[binary]).unwrap();
+        let expected = StringArray::from(vec!["8cde774d6f7333752ed72cacddb05126"]);
+        assert_eq!(result.as_ref(), &expected);
+
+        let args = [Scalar::from(1).to_array(1).unwrap()];
+        assert!(evaluate_function(&ScalarFunction::Md5, &args).is_err());
+    }
+
+    #[test]
+    fn test_reverse() {
+        let strings: ArrayRef = Arc::new(StringArray::from(vec![Some("añö€😀"), Some(""), None]));
+        let result = evaluate_function(&ScalarFunction::Reverse, &[strings]).unwrap();
+        let expected = StringArray::from(vec![Some("😀€öña"), Some(""), None]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_initcap() {
+        let strings: ArrayRef = Arc::new(StringArray::from(vec![
+            Some("hello wORLD  from\tdelta"),
+            Some("élan VITAL"),
+            None,
+        ]));
+        let result = evaluate_function(&ScalarFunction::Initcap, &[strings]).unwrap();
+        let expected = StringArray::from(vec![
+            Some("Hello World  From\tDelta"),
+            Some("Élan Vital"),
+            None,
+        ]);
+        assert_eq!(result.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_like_cache() {
+        let strings = vec![
+            Some("apple"),
+            Some("banana"),
+            None,
+            Some("avocado"),
+            Some("apple"),
+        ];
+        let dictionary: DictionaryArray<Int32Type> = strings.clone().into_iter().collect();
+        let dictionary: ArrayRef = Arc::new(dictionary);
+        let plain: ArrayRef = Arc::new(StringArray::from(strings));
+        let patterns = Scalar::from("a%").to_array(5).unwrap();
+        let expected =
+            BooleanArray::from(vec![Some(true), Some(false), None, Some(true), Some(true)]);
+
+        let naive = evaluate_function(&ScalarFunction::Like, &[plain.clone(), patterns]).unwrap();
+        assert_eq!(naive.as_ref(), &expected);
+
+        let cache = LikeCache::default();
+        assert_eq!(cache.like(&plain, "a%").unwrap().as_ref(), &expected);
+        assert_eq!(cache.like(&dictionary, "a%").unwrap().as_ref(), &expected);
+        // a batch with different keys into the same dictionary reuses the cached matches
+        let keys = Int32Array::from(vec![1, 0]);
+        let values = dictionary.as_any_dictionary().values().clone();
+        let other: ArrayRef = Arc::new(DictionaryArray::new(keys, values));
+        let result = cache.like(&other, "a%").unwrap();
+        assert_eq!(result.as_ref(), &BooleanArray::from(vec![false, true]));
+        assert_eq!(cache.matches.lock().unwrap().len(), 1);
+        let result = cache.like(&other, "%an%").unwrap();
+        assert_eq!(result.as_ref(), &BooleanArray::from(vec![true, false]));
+    }
+}
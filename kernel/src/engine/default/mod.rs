@@ -63,7 +63,7 @@ impl<E: TaskExecutor> DefaultEngine<E> {
             )),
             parquet: Arc::new(DefaultParquetHandler::new(store.clone(), task_executor)),
             store,
-            expression: Arc::new(ArrowExpressionHandler {}),
+            expression: Arc::new(ArrowExpressionHandler::default()),
         })
     }
 
@@ -80,7 +80,7 @@ impl<E: TaskExecutor> DefaultEngine<E> {
             )),
             parquet: Arc::new(DefaultParquetHandler::new(store.clone(), task_executor)),
             store,
-            expression: Arc::new(ArrowExpressionHandler {}),
+            expression: Arc::new(ArrowExpressionHandler::default()),
         }
     }
 
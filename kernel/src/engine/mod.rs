@@ -8,6 +8,9 @@ pub(crate) mod arrow_conversion;
 #[cfg(feature = "arrow-expression")]
 pub mod arrow_expression;
 
+#[cfg(feature = "arrow-expression")]
+pub(crate) mod arrow_functions;
+
 #[cfg(any(feature = "default-engine", feature = "sync-engine"))]
 pub mod arrow_data;
 
@@ -17,6 +20,9 @@ pub(crate) mod arrow_get_data;
 #[cfg(any(feature = "default-engine", feature = "sync-engine"))]
 pub(crate) mod arrow_utils;
 
+#[cfg(any(feature = "default-engine", feature = "sync-engine"))]
+pub mod parquet_row_filter;
+
 #[cfg(feature = "default-engine")]
 pub mod default;
 
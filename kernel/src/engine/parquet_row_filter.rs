@@ -0,0 +1,229 @@
+//! Conversion of kernel [`Expression`]s into [`RowFilter`]s, to push predicates down into the
+//! arrow parquet reader.
+
+use std::sync::Arc;
+
+use arrow_array::cast::AsArray;
+use arrow_array::RecordBatch;
+use arrow_schema::ArrowError;
+use parquet::arrow::arrow_reader::{ArrowPredicate, ArrowPredicateFn, RowFilter};
+use parquet::arrow::{parquet_to_arrow_schema_by_columns, ProjectionMask};
+use parquet::schema::types::SchemaDescriptor;
+
+use crate::engine::arrow_expression::evaluate_expression;
+use crate::expressions::{Expression, UnaryOperator, VariadicOperator};
+use crate::DeltaResult;
+
+/// Convert `expression` into a [`RowFilter`] for reading a parquet file with the given `schema`,
+/// which only keeps the rows for which the expression is true.
+///
+/// Each conjunct of a top-level `AND` is converted separately, and only comparisons between
+/// columns and literals, `IS NULL`, and `AND`, `OR` and `NOT` over those can be converted. A
+/// conjunct that can't be converted, e.g. because it calls a function, references a column that
+/// is not in the file or compares a column with a literal of another type, is left out of the
+/// filter. The filter may therefore keep rows the expression rejects, so readers must still apply
+/// the full expression to the rows it returns. Returns `None` if no part of the expression can be
+/// converted, and an error if the file's schema can't be read as an arrow schema.
+pub fn to_parquet_row_filter(
+    expression: &Expression,
+    schema: &SchemaDescriptor,
+) -> DeltaResult<Option<RowFilter>> {
+    let mut predicates: Vec<Box<dyn ArrowPredicate>> = Vec::new();
+    for conjunct in conjuncts(expression) {
+        let mut leaves = Vec::new();
+        if !collect_predicate_leaves(conjunct, schema, &mut leaves) {
+            continue;
+        }
+        let projection = ProjectionMask::leaves(schema, leaves);
+        if type_checks(conjunct, schema, &projection)? {
+            predicates.push(to_arrow_predicate(conjunct.clone(), projection));
+        }
+    }
+    Ok((!predicates.is_empty()).then(|| RowFilter::new(predicates)))
+}
+
+fn conjuncts(expression: &Expression) -> Vec<&Expression> {
+    match expression {
+        Expression::VariadicOperation {
+            op: VariadicOperator::And,
+            exprs,
+        } => exprs.iter().flat_map(conjuncts).collect(),
+        _ => vec![expression],
+    }
+}
+
+/// Returns whether `expression` is a predicate that can be pushed down, collecting the indices of
+/// the parquet leaf columns it references into `leaves`.
+fn collect_predicate_leaves(
+    expression: &Expression,
+    schema: &SchemaDescriptor,
+    leaves: &mut Vec<usize>,
+) -> bool {
+    match expression {
+        Expression::BinaryOperation { op, left, right } if op.is_comparison() => {
+            collect_operand_leaves(left, schema, leaves)
+                && collect_operand_leaves(right, schema, leaves)
+        }
+        Expression::UnaryOperation {
+            op: UnaryOperator::Not,
+            expr,
+        } => collect_predicate_leaves(expr, schema, leaves),
+        Expression::UnaryOperation {
+            op: UnaryOperator::IsNull,
+            expr,
+        } => collect_operand_leaves(expr, schema, leaves),
+        Expression::VariadicOperation { exprs, .. } => exprs
+            .iter()
+            .all(|expr| collect_predicate_leaves(expr, schema, leaves)),
+        _ => false,
+    }
+}
+
+fn collect_operand_leaves(
+    expression: &Expression,
+    schema: &SchemaDescriptor,
+    leaves: &mut Vec<usize>,
+) -> bool {
+    match expression {
+        Expression::Literal(_) => true,
+        Expression::Column(name) => {
+            let path: Vec<_> = name.split('.').collect();
+            let leaf = schema.columns().iter().position(|column| {
+                let parts = column.path().parts();
+                parts == path || parts == [name.as_str()]
+            });
+            leaf.map(|leaf| leaves.push(leaf)).is_some()
+        }
+        _ => false,
+    }
+}
+
+/// Returns whether `expression` evaluates over the columns of `projection` as the reader will read
+/// them, so that e.g. comparing an `INT64` column with an `INTEGER` literal doesn't fail the read.
+fn type_checks(
+    expression: &Expression,
+    schema: &SchemaDescriptor,
+    projection: &ProjectionMask,
+) -> DeltaResult<bool> {
+    let arrow_schema = parquet_to_arrow_schema_by_columns(schema, projection.clone(), None)?;
+    let batch = RecordBatch::new_empty(Arc::new(arrow_schema));
+    Ok(evaluate_expression(expression, &batch, None).is_ok())
+}
+
+fn to_arrow_predicate(
+    expression: Expression,
+    projection: ProjectionMask,
+) -> Box<dyn ArrowPredicate> {
+    Box::new(ArrowPredicateFn::new(projection, move |batch| {
+        let result = evaluate_expression(&expression, &batch, None)
+            .map_err(|err| ArrowError::from_external_error(Box::new(err)))?;
+        result.as_boolean_opt().cloned().ok_or_else(|| {
+            ArrowError::InvalidArgumentError(format!(
+                "Predicate {expression} evaluated to {} instead of a boolean",
+                result.data_type()
+            ))
+        })
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::types::Int32Type;
+    use arrow_array::{Int32Array, Int64Array, StringArray};
+    use bytes::Bytes;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use parquet::arrow::ArrowWriter;
+
+    use super::*;
+
+    fn write_parquet() -> Bytes {
+        let a = Int32Array::from(vec![1, 2, 3, 4, 5]);
+        let b = StringArray::from(vec![Some("x"), Some("y"), Some("x"), None, Some("x")]);
+        let c = Int64Array::from(vec![10, 20, 30, 40, 50]);
+        let batch = RecordBatch::try_from_iter([
+            ("a", Arc::new(a) as _),
+            ("b", Arc::new(b) as _),
+            ("c", Arc::new(c) as _),
+        ])
+        .unwrap();
+        let mut data = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut data, batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        data.into()
+    }
+
+    /// Read the parquet file with the row filter for `expression` (if any), returning column `a`
+    fn read_filtered(data: &Bytes, expression: &Expression) -> (bool, Vec<i32>) {
+        let mut builder = ParquetRecordBatchReaderBuilder::try_new(data.clone()).unwrap();
+        let filter = to_parquet_row_filter(expression, builder.parquet_schema()).unwrap();
+        let has_filter = filter.is_some();
+        if let Some(filter) = filter {
+            builder = builder.with_row_filter(filter);
+        }
+        let values = builder
+            .build()
+            .unwrap()
+            .flat_map(|batch| {
+                let batch = batch.unwrap();
+                let a = batch.column(0).as_primitive::<Int32Type>();
+                a.values().to_vec()
+            })
+            .collect();
+        (has_filter, values)
+    }
+
+    #[test]
+    fn test_to_parquet_row_filter() {
+        let data = write_parquet();
+        let expression = Expression::column("a")
+            .gt(Expression::literal(2))
+            .and(Expression::column("b").eq(Expression::literal("x")));
+        assert_eq!(read_filtered(&data, &expression), (true, vec![3, 5]));
+
+        let expression = Expression::column("b")
+            .is_null()
+            .or(Expression::column("a").lt_eq(Expression::literal(1)));
+        assert_eq!(read_filtered(&data, &expression), (true, vec![1, 4]));
+    }
+
+    #[test]
+    fn test_to_parquet_row_filter_partial() {
+        let data = write_parquet();
+        // the SPLIT_PART conjunct and the conjunct on a column missing from the file are left out
+        let expression = Expression::and_from([
+            Expression::column("a").gt(Expression::literal(2)),
+            Expression::column("b")
+                .split_part(Expression::literal("/"), Expression::literal(1))
+                .eq(Expression::literal("y")),
+            Expression::column("d").eq(Expression::literal(1)),
+        ]);
+        assert_eq!(read_filtered(&data, &expression), (true, vec![3, 4, 5]));
+
+        let expression = Expression::column("d").eq(Expression::literal(1));
+        assert_eq!(
+            read_filtered(&data, &expression),
+            (false, vec![1, 2, 3, 4, 5])
+        );
+    }
+
+    #[test]
+    fn test_to_parquet_row_filter_type_mismatch() {
+        let data = write_parquet();
+        // `c` is INT64, so comparing it with an INTEGER literal doesn't evaluate and is left out
+        let expression = Expression::and_from([
+            Expression::column("a").gt(Expression::literal(2)),
+            Expression::column("c").gt(Expression::literal(20)),
+        ]);
+        assert_eq!(read_filtered(&data, &expression), (true, vec![3, 4, 5]));
+
+        let expression = Expression::column("c").gt(Expression::literal(20));
+        assert_eq!(
+            read_filtered(&data, &expression),
+            (false, vec![1, 2, 3, 4, 5])
+        );
+
+        let expression = Expression::column("c").gt(Expression::literal(20i64));
+        assert_eq!(read_filtered(&data, &expression), (true, vec![3, 4, 5]));
+    }
+}
@@ -25,7 +25,7 @@ impl SyncEngine {
             fs_client: Arc::new(fs_client::SyncFilesystemClient {}),
             json_handler: Arc::new(json::SyncJsonHandler {}),
             parquet_handler: Arc::new(parquet::SyncParquetHandler {}),
-            expression_handler: Arc::new(ArrowExpressionHandler {}),
+            expression_handler: Arc::new(ArrowExpressionHandler::default()),
         }
     }
 }
@@ -155,6 +155,14 @@ pub enum Error {
     /// Expressions did not parse or evaluate correctly
     #[error("Invalid expression evaluation: {0}")]
     InvalidExpressionEvaluation(String),
+
+    /// A data type could not be represented as an arrow data type
+    #[error("Unsupported data type: {data_type}")]
+    UnsupportedDataType { data_type: DataType },
+
+    /// Evaluating an expression allocated more memory than it was allowed to
+    #[error("Expression evaluation exceeded its allocation limit of {limit} bytes")]
+    AllocationLimitExceeded { limit: usize },
 }
 
 // Convenience constructors for Error types that take a String argument
@@ -203,6 +211,15 @@ impl Error {
     pub fn invalid_expression(msg: impl ToString) -> Self {
         Self::InvalidExpressionEvaluation(msg.to_string())
     }
+    pub fn unsupported_data_type(data_type: impl Into<DataType>) -> Self {
+        Self::UnsupportedDataType {
+            data_type: data_type.into(),
+        }
+    }
+
+    pub fn allocation_limit_exceeded(limit: usize) -> Self {
+        Self::AllocationLimitExceeded { limit }
+    }
 
     pub fn internal_error(msg: impl ToString) -> Self {
         Self::InternalError(msg.to_string()).with_backtrace()
@@ -242,7 +259,15 @@ from_with_backtrace!(
 #[cfg(any(feature = "default-engine", feature = "sync-engine"))]
 impl From<arrow_schema::ArrowError> for Error {
     fn from(value: arrow_schema::ArrowError) -> Self {
-        Self::Arrow(value).with_backtrace()
+        match value {
+            // Kernel errors raised inside arrow conversions have to be wrapped in an `ArrowError`.
+            // Unwrap them again so callers can match on the original error.
+            arrow_schema::ArrowError::ExternalError(source) if source.is::<Self>() => {
+                // Safety: we just checked that `source` is a kernel error
+                *source.downcast().unwrap()
+            }
+            value => Self::Arrow(value).with_backtrace(),
+        }
     }
 }
 
@@ -5,8 +5,12 @@ use std::fmt::{Display, Formatter};
 
 use itertools::Itertools;
 
+use crate::schema::{DataType, StructField, StructType};
+use crate::{DeltaResult, Error};
+
 pub use self::scalars::{ArrayData, Scalar, StructData};
 
+mod parser;
 mod scalars;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -111,6 +115,77 @@ impl Display for BinaryOperator {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+/// A scalar function, which computes one output value per row from its arguments.
+pub enum ScalarFunction {
+    /// `SPLIT_PART(str, delimiter, n)`: The n-th field (1-based, negative counts from the end) of
+    /// `str` split on `delimiter`, or an empty string if there is no such field.
+    SplitPart,
+    /// `REPLACE(str, from, to)`: `str` with every occurrence of `from` replaced by `to`. An empty
+    /// `from` leaves `str` unchanged.
+    Replace,
+    /// `TYPEOF(expr)`: The name of the arrow data type of `expr`, for every row. Intended for
+    /// debugging schema issues, since the name depends on the engine's in-memory representation.
+    TypeOf,
+    /// `COALESCE(expr, ...)`: The first of the arguments that is not null, or null if all of them
+    /// are null.
+    Coalesce,
+    /// `FORMAT_NUMBER(x, d)`: The number `x` formatted with `d` decimal places and with commas
+    /// separating the thousands, like `12,345.68`, or null if `d` is negative. Integers and
+    /// decimals are formatted from their exact values, rounding half to even, and `d` may be at
+    /// most 65535.
+    FormatNumber,
+    /// `NVL(expr, default)`: `expr` if it is not null, otherwise `default`. The same as a
+    /// `COALESCE` of two arguments.
+    Nvl,
+    /// `NVL2(expr, if_not_null, if_null)`: `if_not_null` if `expr` is not null, otherwise
+    /// `if_null`.
+    Nvl2,
+    /// `MONOTONICALLY_INCREASING_ID(partition)`: A 64-bit id for each row that is unique across
+    /// partitions and deterministic given the same partition index and row order. Like Spark's
+    /// function of the same name, the upper 31 bits hold the `partition` index (which must be
+    /// between 0 and 2^30 - 1, so that ids are never negative) and the lower 33 bits hold the
+    /// offset of the row within the batch.
+    MonotonicallyIncreasingId,
+    /// `MD5(expr)`: The MD5 digest of the string or binary `expr`, as a hex string. Requires the
+    /// `hash-functions` feature.
+    Md5,
+    /// `SHA2(expr, bits)`: The SHA-2 digest of the string or binary `expr`, as a hex string, where
+    /// `bits` is one of 224, 256, 384 or 512 (or 0, meaning 256) and any other value gives null.
+    /// Requires the `hash-functions` feature.
+    Sha2,
+    /// `REVERSE(str)`: `str` with its characters in reverse order. Characters are Unicode scalar
+    /// values, so a multibyte character stays intact (though a combining mark ends up before its
+    /// base character rather than after it).
+    Reverse,
+    /// `INITCAP(str)`: `str` with the first letter of each word in uppercase and every other letter
+    /// in lowercase, where words are separated by whitespace.
+    Initcap,
+    /// `LIKE(str, pattern)`: Whether `str` matches the SQL `pattern`, in which `%` matches any
+    /// sequence of characters, `_` matches any single character, and `\` escapes either.
+    Like,
+}
+
+impl Display for ScalarFunction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SplitPart => write!(f, "SPLIT_PART"),
+            Self::Replace => write!(f, "REPLACE"),
+            Self::TypeOf => write!(f, "TYPEOF"),
+            Self::Coalesce => write!(f, "COALESCE"),
+            Self::FormatNumber => write!(f, "FORMAT_NUMBER"),
+            Self::Nvl => write!(f, "NVL"),
+            Self::Nvl2 => write!(f, "NVL2"),
+            Self::MonotonicallyIncreasingId => write!(f, "MONOTONICALLY_INCREASING_ID"),
+            Self::Md5 => write!(f, "MD5"),
+            Self::Sha2 => write!(f, "SHA2"),
+            Self::Reverse => write!(f, "REVERSE"),
+            Self::Initcap => write!(f, "INITCAP"),
+            Self::Like => write!(f, "LIKE"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 /// A unary operator.
 pub enum UnaryOperator {
@@ -155,6 +230,35 @@ pub enum Expression {
         /// The expressions.
         exprs: Vec<Expression>,
     },
+    /// A cast of an expression to a data type.
+    Cast {
+        /// The expression.
+        expr: Box<Expression>,
+        /// The data type to cast to.
+        data_type: DataType,
+    },
+    /// A conditional expression `CASE WHEN cond THEN result ... ELSE otherwise END`.
+    Case {
+        /// The `(condition, result)` pairs, in order. The result of the first branch whose
+        /// condition is true is chosen.
+        branches: Vec<(Expression, Expression)>,
+        /// The result if no condition is true. Null if absent.
+        otherwise: Option<Box<Expression>>,
+    },
+    /// An expression with a name for its result, e.g. for the output field of a projection.
+    Alias {
+        /// The expression.
+        expr: Box<Expression>,
+        /// The name of the result.
+        name: String,
+    },
+    /// A scalar function call.
+    Function {
+        /// The function.
+        func: ScalarFunction,
+        /// The arguments.
+        args: Vec<Expression>,
+    },
     // TODO: support more expressions, such as IS IN, LIKE, etc.
 }
 
@@ -200,6 +304,28 @@ impl Display for Expression {
                     )
                 }
             },
+            Self::Cast { expr, data_type } => write!(f, "CAST({expr} AS {data_type})"),
+            Self::Alias { expr, name } => write!(f, "{expr} AS {name}"),
+            Self::Case {
+                branches,
+                otherwise,
+            } => {
+                write!(f, "CASE")?;
+                for (condition, result) in branches {
+                    write!(f, " WHEN {condition} THEN {result}")?;
+                }
+                if let Some(otherwise) = otherwise {
+                    write!(f, " ELSE {otherwise}")?;
+                }
+                write!(f, " END")
+            }
+            Self::Function { func, args } => {
+                write!(
+                    f,
+                    "{func}({})",
+                    &args.iter().map(|e| format!("{e}")).join(", ")
+                )
+            }
         }
     }
 }
@@ -260,6 +386,12 @@ impl Expression {
         Self::VariadicOperation { op, exprs }
     }
 
+    /// Creates a new scalar function call FUNC(args...)
+    pub fn function(func: ScalarFunction, args: impl IntoIterator<Item = Self>) -> Self {
+        let args = args.into_iter().collect::<Vec<_>>();
+        Self::Function { func, args }
+    }
+
     /// Creates a new expression AND(exprs...)
     pub fn and_from(exprs: impl IntoIterator<Item = Self>) -> Self {
         Self::variadic(VariadicOperator::And, exprs)
@@ -330,32 +462,340 @@ impl Expression {
         Self::binary(BinaryOperator::Distinct, self, other)
     }
 
+    /// Create a new expression `CAST(self AS data_type)`
+    pub fn cast(self, data_type: DataType) -> Self {
+        Self::Cast {
+            expr: Box::new(self),
+            data_type,
+        }
+    }
+
+    /// Create a new expression `self AS name`
+    pub fn alias(self, name: impl Into<String>) -> Self {
+        Self::Alias {
+            expr: Box::new(self),
+            name: name.into(),
+        }
+    }
+
+    /// Create a new expression `SPLIT_PART(self, delimiter, n)`
+    pub fn split_part(self, delimiter: Self, n: Self) -> Self {
+        Self::function(ScalarFunction::SplitPart, [self, delimiter, n])
+    }
+
+    /// Create a new expression `REPLACE(self, from, to)`
+    pub fn replace(self, from: Self, to: Self) -> Self {
+        Self::function(ScalarFunction::Replace, [self, from, to])
+    }
+
+    /// Create a new expression `REVERSE(self)`
+    pub fn reverse(self) -> Self {
+        Self::function(ScalarFunction::Reverse, [self])
+    }
+
+    /// Create a new expression `INITCAP(self)`
+    pub fn initcap(self) -> Self {
+        Self::function(ScalarFunction::Initcap, [self])
+    }
+
+    /// Create a new expression `LIKE(self, pattern)`
+    pub fn like(self, pattern: Self) -> Self {
+        Self::function(ScalarFunction::Like, [self, pattern])
+    }
+
+    /// Create a new expression `TYPEOF(self)`
+    pub fn type_of(self) -> Self {
+        Self::function(ScalarFunction::TypeOf, [self])
+    }
+
+    /// Start building a new expression `CASE WHEN condition THEN result ... END`. More branches can
+    /// be added with [`CaseBuilder::when`], and the expression is finished with
+    /// [`CaseBuilder::otherwise`] or [`CaseBuilder::end`].
+    pub fn when(condition: Self, result: Self) -> CaseBuilder {
+        CaseBuilder {
+            branches: vec![(condition, result)],
+        }
+    }
+
+    /// Create a new expression `FORMAT_NUMBER(self, decimals)`
+    pub fn format_number(self, decimals: Self) -> Self {
+        Self::function(ScalarFunction::FormatNumber, [self, decimals])
+    }
+
+    /// Create a new expression `NVL(self, default)`
+    pub fn nvl(self, default: Self) -> Self {
+        Self::function(ScalarFunction::Nvl, [self, default])
+    }
+
+    /// Create a new expression `NVL2(self, if_not_null, if_null)`
+    pub fn nvl2(self, if_not_null: Self, if_null: Self) -> Self {
+        Self::function(ScalarFunction::Nvl2, [self, if_not_null, if_null])
+    }
+
+    /// Create a new expression `MD5(self)`
+    pub fn md5(self) -> Self {
+        Self::function(ScalarFunction::Md5, [self])
+    }
+
+    /// Create a new expression `SHA2(self, bits)`
+    pub fn sha2(self, bits: Self) -> Self {
+        Self::function(ScalarFunction::Sha2, [self, bits])
+    }
+
+    /// Create a new expression `MONOTONICALLY_INCREASING_ID(partition)`
+    pub fn monotonically_increasing_id(partition: impl Into<Self>) -> Self {
+        Self::function(
+            ScalarFunction::MonotonicallyIncreasingId,
+            [partition.into()],
+        )
+    }
+
+    /// Create a new expression `COALESCE(exprs...)`
+    pub fn coalesce(exprs: impl IntoIterator<Item = Self>) -> Self {
+        Self::function(ScalarFunction::Coalesce, exprs)
+    }
+
+    /// Whether this expression can evaluate to null over data with the given `schema`, e.g. to
+    /// decide the nullability of the output field of a projection. This is conservative: a column
+    /// that is not in the schema is assumed to be nullable, and so is any operation on a nullable
+    /// operand, even though some (like `NULL AND false`) can't actually produce a null.
+    pub fn is_nullable(&self, schema: &StructType) -> bool {
+        match self {
+            Self::Literal(value) => value.is_null(),
+            Self::Column(name) => is_column_nullable(schema, name),
+            Self::Struct(_) => false,
+            Self::BinaryOperation {
+                op: BinaryOperator::Distinct,
+                ..
+            } => false,
+            Self::UnaryOperation {
+                op: UnaryOperator::IsNull,
+                ..
+            } => false,
+            Self::Function {
+                func: ScalarFunction::TypeOf,
+                ..
+            } => false,
+            // the result is only null if all the arguments are
+            Self::Function {
+                func: ScalarFunction::Coalesce | ScalarFunction::Nvl,
+                args,
+            } => args.iter().all(|arg| arg.is_nullable(schema)),
+            // the first argument only chooses between the others
+            Self::Function {
+                func: ScalarFunction::Nvl2,
+                args,
+            } => args.iter().skip(1).any(|arg| arg.is_nullable(schema)),
+            // the result is also null for a negative number of decimals
+            Self::Function {
+                func: ScalarFunction::FormatNumber,
+                args,
+            } => {
+                let non_negative_decimals =
+                    matches!(args.get(1), Some(Self::Literal(Scalar::Integer(d))) if *d >= 0);
+                !non_negative_decimals || args.iter().any(|arg| arg.is_nullable(schema))
+            }
+            // the result is also null for an unsupported number of bits
+            Self::Function {
+                func: ScalarFunction::Sha2,
+                args,
+            } => {
+                let supported_bits = matches!(
+                    args.get(1),
+                    Some(Self::Literal(Scalar::Integer(0 | 224 | 256 | 384 | 512)))
+                );
+                !supported_bits || args.iter().any(|arg| arg.is_nullable(schema))
+            }
+            Self::BinaryOperation { left, right, .. } => {
+                left.is_nullable(schema) || right.is_nullable(schema)
+            }
+            Self::UnaryOperation { expr, .. }
+            | Self::Cast { expr, .. }
+            | Self::Alias { expr, .. } => expr.is_nullable(schema),
+            Self::Case {
+                branches,
+                otherwise,
+            } => {
+                // without an ELSE, the expression is null if no branch matches
+                let otherwise_nullable = match otherwise {
+                    Some(otherwise) => otherwise.is_nullable(schema),
+                    None => true,
+                };
+                otherwise_nullable
+                    || branches
+                        .iter()
+                        .any(|(_, result)| result.is_nullable(schema))
+            }
+            Self::VariadicOperation { exprs: args, .. } | Self::Function { args, .. } => {
+                args.iter().any(|arg| arg.is_nullable(schema))
+            }
+        }
+    }
+
+    /// The direct sub-expressions of this expression, in order, or nothing for a leaf like a
+    /// literal or a column reference.
+    pub fn children(&self) -> Vec<&Self> {
+        match self {
+            Self::Literal(_) | Self::Column(_) => vec![],
+            Self::Struct(exprs)
+            | Self::VariadicOperation { exprs, .. }
+            | Self::Function { args: exprs, .. } => exprs.iter().collect(),
+            Self::BinaryOperation { left, right, .. } => vec![left, right],
+            Self::UnaryOperation { expr, .. }
+            | Self::Cast { expr, .. }
+            | Self::Alias { expr, .. } => vec![expr],
+            Self::Case {
+                branches,
+                otherwise,
+            } => branches
+                .iter()
+                .flat_map(|(condition, result)| [condition, result])
+                .chain(otherwise.as_deref())
+                .collect(),
+        }
+    }
+
+    /// Rewrite every literal that is compared with, or used in arithmetic with, a column of the
+    /// given `schema` to the type of that column, e.g. to push the expression down into a reader
+    /// without coercing it per row. Literals that can't be converted without losing information are
+    /// kept as they are if both sides are numbers (evaluation may still be able to combine them),
+    /// and are otherwise an error.
+    pub fn coerce_literals_to_schema(self, schema: &StructType) -> DeltaResult<Self> {
+        let coerce = |expr: Self| expr.coerce_literals_to_schema(schema);
+        let coerce_all = |exprs: Vec<Self>| -> DeltaResult<Vec<Self>> {
+            exprs.into_iter().map(coerce).collect()
+        };
+        let expr = match self {
+            Self::Literal(_) | Self::Column(_) => self,
+            Self::BinaryOperation {
+                op: op @ (BinaryOperator::In | BinaryOperator::NotIn),
+                left,
+                right,
+            } => Self::binary(op, coerce(*left)?, coerce(*right)?),
+            Self::BinaryOperation { op, left, right } => {
+                let (left, right) = match (*left, *right) {
+                    (Self::Column(name), Self::Literal(value)) => {
+                        let value = coerce_literal_to_column(schema, &name, value)?;
+                        (Self::Column(name), value)
+                    }
+                    (Self::Literal(value), Self::Column(name)) => {
+                        let value = coerce_literal_to_column(schema, &name, value)?;
+                        (value, Self::Column(name))
+                    }
+                    (left, right) => (coerce(left)?, coerce(right)?),
+                };
+                Self::binary(op, left, right)
+            }
+            Self::UnaryOperation { op, expr } => Self::unary(op, coerce(*expr)?),
+            Self::Cast { expr, data_type } => coerce(*expr)?.cast(data_type),
+            Self::Alias { expr, name } => coerce(*expr)?.alias(name),
+            Self::Struct(exprs) => Self::Struct(coerce_all(exprs)?),
+            Self::VariadicOperation { op, exprs } => Self::variadic(op, coerce_all(exprs)?),
+            Self::Function { func, args } => Self::function(func, coerce_all(args)?),
+            Self::Case {
+                branches,
+                otherwise,
+            } => Self::Case {
+                branches: branches
+                    .into_iter()
+                    .map(|(condition, result)| Ok((coerce(condition)?, coerce(result)?)))
+                    .collect::<DeltaResult<_>>()?,
+                otherwise: otherwise.map(|o| coerce(*o).map(Box::new)).transpose()?,
+            },
+        };
+        Ok(expr)
+    }
+
     fn walk(&self) -> impl Iterator<Item = &Self> + '_ {
         let mut stack = vec![self];
         std::iter::from_fn(move || {
             let expr = stack.pop()?;
-            match expr {
-                Self::Literal(_) => {}
-                Self::Column { .. } => {}
-                Self::Struct(exprs) => {
-                    stack.extend(exprs.iter());
-                }
-                Self::BinaryOperation { left, right, .. } => {
-                    stack.push(left);
-                    stack.push(right);
-                }
-                Self::UnaryOperation { expr, .. } => {
-                    stack.push(expr);
-                }
-                Self::VariadicOperation { exprs, .. } => {
-                    stack.extend(exprs.iter());
-                }
-            }
+            stack.extend(expr.children());
             Some(expr)
         })
     }
 }
 
+/// A builder for a [`Expression::Case`], created by [`Expression::when`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaseBuilder {
+    branches: Vec<(Expression, Expression)>,
+}
+
+impl CaseBuilder {
+    /// Add a branch `WHEN condition THEN result`, which is only checked if no earlier branch
+    /// matched.
+    pub fn when(mut self, condition: Expression, result: Expression) -> Self {
+        self.branches.push((condition, result));
+        self
+    }
+
+    /// Finish the expression with `ELSE otherwise END`.
+    pub fn otherwise(self, otherwise: Expression) -> Expression {
+        Expression::Case {
+            branches: self.branches,
+            otherwise: Some(Box::new(otherwise)),
+        }
+    }
+
+    /// Finish the expression without an `ELSE`, so it is null when no branch matches.
+    pub fn end(self) -> Expression {
+        Expression::Case {
+            branches: self.branches,
+            otherwise: None,
+        }
+    }
+}
+
+fn coerce_literal_to_column(
+    schema: &StructType,
+    name: &str,
+    value: Scalar,
+) -> DeltaResult<Expression> {
+    let Some(data_type) = find_schema_field(schema, name).map(StructField::data_type) else {
+        return Ok(Expression::Literal(value));
+    };
+    if let Some(coerced) = value.coerce_to(data_type) {
+        return Ok(Expression::Literal(coerced));
+    }
+    if value.data_type().is_numeric() && data_type.is_numeric() {
+        return Ok(Expression::Literal(value));
+    }
+    Err(Error::invalid_expression(format!(
+        "Cannot coerce literal {value} to the type {data_type} of column {name}"
+    )))
+}
+
+/// The field of `schema` for a column, if it has one by that name. A `.` in the name separates the
+/// steps of a path to a nested column, unless the schema has a top-level field whose name matches
+/// the full name.
+fn find_schema_field<'a>(schema: &'a StructType, name: &str) -> Option<&'a StructField> {
+    if let Some(field) = schema.field(name) {
+        return Some(field);
+    }
+    let mut path = name.split('.');
+    let mut field = schema.field(path.next()?)?;
+    for step in path {
+        let DataType::Struct(struct_type) = field.data_type() else {
+            return None;
+        };
+        field = struct_type.field(step)?;
+    }
+    Some(field)
+}
+
+/// A column is nullable if it, or any of the structs on the path to it, is nullable (or if
+/// `schema` doesn't have it).
+fn is_column_nullable(schema: &StructType, name: &str) -> bool {
+    if let Some(field) = schema.field(name) {
+        return field.is_nullable();
+    }
+    let structs = name.match_indices('.').map(|(end, _)| &name[..end]);
+    structs
+        .chain([name])
+        .any(|path| !matches!(find_schema_field(schema, path), Some(field) if !field.is_nullable()))
+}
+
 impl std::ops::Not for Expression {
     type Output = Self;
 
@@ -398,7 +838,8 @@ impl std::ops::Div<Expression> for Expression {
 
 #[cfg(test)]
 mod tests {
-    use super::Expression as Expr;
+    use super::{Expression as Expr, Scalar};
+    use crate::schema::{DataType, StructField, StructType};
 
     #[test]
     fn test_expression_format() {
@@ -436,6 +877,22 @@ mod tests {
                     .or(col_ref.clone().lt(Expr::literal(10))),
                 "OR(Column(x) > 2, Column(x) < 10)",
             ),
+            (
+                col_ref
+                    .clone()
+                    .split_part(Expr::literal("/"), Expr::literal(2)),
+                "SPLIT_PART(Column(x), '/', 2)",
+            ),
+            (
+                col_ref.clone().cast(DataType::LONG),
+                "CAST(Column(x) AS long)",
+            ),
+            (
+                Expr::when(col_ref.clone().gt(Expr::literal(1)), Expr::literal("a"))
+                    .when(col_ref.clone().gt(Expr::literal(0)), Expr::literal("b"))
+                    .otherwise(Expr::literal("c")),
+                "CASE WHEN Column(x) > 1 THEN 'a' WHEN Column(x) > 0 THEN 'b' ELSE 'c' END",
+            ),
             (col_ref.eq(Expr::literal("foo")), "Column(x) = 'foo'"),
         ];
 
@@ -444,4 +901,116 @@ mod tests {
             assert_eq!(result, expected);
         }
     }
+
+    #[test]
+    fn test_is_nullable() {
+        let schema = StructType::new(vec![
+            StructField::new("a", DataType::INTEGER, true),
+            StructField::new("b", DataType::INTEGER, false),
+            StructField::new(
+                "s",
+                StructType::new(vec![StructField::new("c", DataType::INTEGER, false)]),
+                false,
+            ),
+        ]);
+        let a = Expr::column("a");
+        let b = Expr::column("b");
+        let cases = [
+            (a.clone(), true),
+            (b.clone(), false),
+            (Expr::column("s.c"), false),
+            (Expr::column("missing"), true),
+            (Expr::literal(1), false),
+            (Expr::literal(Scalar::Null(DataType::INTEGER)), true),
+            (a.clone() + b.clone(), true),
+            (b.clone() + Expr::literal(1), false),
+            (a.clone().is_null(), false),
+            (Expr::coalesce([a.clone(), b.clone()]), false),
+            (Expr::coalesce([a.clone(), Expr::literal(0)]), false),
+            (Expr::coalesce([a.clone(), a.clone()]), true),
+            (a.clone().nvl(b.clone()), false),
+            (a.clone().nvl2(b.clone(), Expr::literal(0)), false),
+            (b.clone().nvl2(a.clone(), Expr::literal(0)), true),
+            (b.clone().format_number(Expr::literal(2)), false),
+            (b.clone().format_number(Expr::literal(-2)), true),
+            (b.clone().format_number(b.clone()), true),
+            (Expr::when(a.clone().is_null(), b.clone()).end(), true),
+            (
+                Expr::when(a.clone().is_null(), b.clone()).otherwise(Expr::literal(0)),
+                false,
+            ),
+            (
+                Expr::coalesce([a, Expr::literal(Scalar::Null(DataType::INTEGER))]),
+                true,
+            ),
+        ];
+        for (expr, expected) in cases {
+            assert_eq!(expr.is_nullable(&schema), expected, "{expr}");
+        }
+    }
+
+    #[test]
+    fn test_children() {
+        let a = Expr::column("a");
+        let b = Expr::column("b");
+        let lit = Expr::literal(1);
+
+        let expr = Expr::and_from([a.clone(), b.clone(), lit.clone()]);
+        assert_eq!(expr.children(), vec![&a, &b, &lit]);
+
+        let expr = a.clone().lt(lit.clone());
+        assert_eq!(expr.children(), vec![&a, &lit]);
+
+        assert!(lit.children().is_empty());
+        assert!(a.children().is_empty());
+    }
+
+    #[test]
+    fn test_coerce_literals_to_schema() {
+        let schema = StructType::new(vec![
+            StructField::new("l", DataType::LONG, true),
+            StructField::new("d", DataType::decimal(10, 2).unwrap(), true),
+            StructField::new("s", DataType::STRING, true),
+            StructField::new("i", DataType::INTEGER, true),
+        ]);
+        let l = Expr::column("l");
+        let cases = [
+            (
+                l.clone().gt(Expr::literal(5)),
+                l.clone().gt(Expr::literal(Scalar::Long(5))),
+            ),
+            (
+                Expr::literal(5)
+                    .lt(l.clone())
+                    .and(Expr::column("s").eq("x".into())),
+                Expr::literal(Scalar::Long(5))
+                    .lt(l.clone())
+                    .and(Expr::column("s").eq("x".into())),
+            ),
+            (
+                Expr::column("d") + Expr::literal(3),
+                Expr::column("d") + Expr::literal(Scalar::Decimal(300, 10, 2)),
+            ),
+            (
+                l.clone().eq(Expr::literal(Scalar::Null(DataType::INTEGER))),
+                l.clone().eq(Expr::literal(Scalar::Null(DataType::LONG))),
+            ),
+            // too large for the column's type, but evaluation may still handle it
+            (
+                Expr::column("i").gt(Expr::literal(Scalar::Long(1 << 40))),
+                Expr::column("i").gt(Expr::literal(Scalar::Long(1 << 40))),
+            ),
+            // not a column of the schema
+            (
+                Expr::column("x").gt(Expr::literal(5)),
+                Expr::column("x").gt(Expr::literal(5)),
+            ),
+        ];
+        for (expr, expected) in cases {
+            assert_eq!(expr.coerce_literals_to_schema(&schema).unwrap(), expected);
+        }
+
+        let expr = Expr::column("s").gt(Expr::literal(5));
+        assert!(expr.coerce_literals_to_schema(&schema).is_err());
+    }
 }
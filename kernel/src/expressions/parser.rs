@@ -0,0 +1,510 @@
+//! A minimal parser of SQL-like predicates, like `a > 2 AND b = 'x'`, into [`Expression`]s.
+
+use std::fmt::{Display, Formatter};
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+use super::{find_schema_field, BinaryOperator, Expression, Scalar};
+use crate::schema::{DataType, PrimitiveType, SchemaRef, StructField};
+use crate::{DeltaResult, Error};
+
+impl Expression {
+    /// Parse a SQL-like predicate, like `a > 2 AND b = 'x'`, for expressions that would be tedious
+    /// to build by hand, e.g. in tests and tools. The syntax supported is:
+    ///
+    /// - `AND`, `OR` and `NOT` (case-insensitive, with the usual precedence) and parentheses
+    /// - The comparisons `=`, `!=` (or `<>`), `<`, `<=`, `>` and `>=`, and `IS [NOT] NULL`
+    /// - Integer, decimal, single-quoted string (with `''` for a quote), `TRUE`/`FALSE` and `NULL`
+    ///   literals
+    /// - References to the columns of `schema`, with `.` separating the steps of a path to a nested
+    ///   column, and backticks quoting a name that isn't a plain identifier, like `` `my col` ``
+    ///
+    /// Functions and arithmetic are not supported, and `NOT`s and parentheses may only be nested
+    /// 64 deep. Literals compared with a column are converted to the type of that column, as by
+    /// [`Expression::coerce_literals_to_schema`], unless they don't fit in it, in which case the
+    /// column is cast to the wider type of the literal instead, like `CAST(a AS LONG) > 3000000000`
+    /// for an `INTEGER` column `a`.
+    pub fn parse(sql: &str, schema: &SchemaRef) -> DeltaResult<Self> {
+        let mut parser = Parser {
+            tokens: tokenize(sql)?,
+            position: 0,
+            end: sql.len(),
+            depth: 0,
+            schema,
+        };
+        let expression = parser.parse_or()?;
+        if let Some((offset, token)) = parser.tokens.get(parser.position) {
+            return Err(syntax_error(*offset, format!("unexpected {token}")));
+        }
+        expression.coerce_literals_to_schema(schema)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Identifier(String),
+    QuotedIdentifier(String),
+    Number(String),
+    String(String),
+    Symbol(&'static str),
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Identifier(name) | Self::Number(name) => write!(f, "'{name}'"),
+            Self::QuotedIdentifier(name) => write!(f, "'`{name}`'"),
+            Self::String(value) => write!(f, "string '{value}'"),
+            Self::Symbol(symbol) => write!(f, "'{symbol}'"),
+        }
+    }
+}
+
+fn syntax_error(offset: usize, message: impl Display) -> Error {
+    Error::invalid_expression(format!("Syntax error at position {offset}: {message}"))
+}
+
+/// How deep `NOT`s and parentheses may be nested, to bound the recursion of parsing them.
+const MAX_DEPTH: usize = 64;
+
+/// The symbols, longest first so that e.g. `<=` isn't read as `<` followed by `=`.
+const SYMBOLS: [&str; 12] = [
+    "<=", ">=", "!=", "<>", "==", "=", "<", ">", "(", ")", ".", "-",
+];
+
+fn tokenize(sql: &str) -> DeltaResult<Vec<(usize, Token)>> {
+    let mut tokens = Vec::new();
+    let mut chars = sql.char_indices().peekable();
+    while let Some(&(offset, c)) = chars.peek() {
+        let token = match c {
+            c if c.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                Token::Identifier(take_while(&mut chars, |c| c.is_alphanumeric() || c == '_'))
+            }
+            c if c.is_ascii_digit() => {
+                Token::Number(take_while(&mut chars, |c| c.is_ascii_digit() || c == '.'))
+            }
+            '\'' => Token::String(take_quoted(&mut chars, offset, '\'')?),
+            '`' => Token::QuotedIdentifier(take_quoted(&mut chars, offset, '`')?),
+            _ => {
+                let symbol = SYMBOLS
+                    .into_iter()
+                    .find(|symbol| sql[offset..].starts_with(symbol))
+                    .ok_or_else(|| syntax_error(offset, format!("unexpected character '{c}'")))?;
+                for _ in 0..symbol.len() {
+                    chars.next();
+                }
+                Token::Symbol(symbol)
+            }
+        };
+        tokens.push((offset, token));
+    }
+    Ok(tokens)
+}
+
+fn take_while(chars: &mut Peekable<CharIndices<'_>>, predicate: impl Fn(char) -> bool) -> String {
+    let mut result = String::new();
+    while let Some((_, c)) = chars.next_if(|(_, c)| predicate(*c)) {
+        result.push(c);
+    }
+    result
+}
+
+/// The contents between the `quote` at `offset` and the matching closing one, in which a doubled
+/// `quote` stands for the quote itself.
+fn take_quoted(
+    chars: &mut Peekable<CharIndices<'_>>,
+    offset: usize,
+    quote: char,
+) -> DeltaResult<String> {
+    chars.next();
+    let mut result = String::new();
+    loop {
+        match chars.next() {
+            Some((_, c)) if c == quote => match chars.next_if(|(_, c)| *c == quote) {
+                Some(_) => result.push(quote),
+                None => return Ok(result),
+            },
+            Some((_, c)) => result.push(c),
+            None => return Err(syntax_error(offset, format!("unterminated {quote}"))),
+        }
+    }
+}
+
+struct Parser<'a> {
+    tokens: Vec<(usize, Token)>,
+    position: usize,
+    /// The length of the input, for errors at its end.
+    end: usize,
+    /// How many `NOT`s and parentheses enclose the current position.
+    depth: usize,
+    schema: &'a SchemaRef,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(_, token)| token)
+    }
+
+    /// The offset of the next token, or of the end of the input if there are no more tokens.
+    fn offset(&self) -> usize {
+        match self.tokens.get(self.position) {
+            Some((offset, _)) => *offset,
+            None => self.end,
+        }
+    }
+
+    fn next(&mut self) -> DeltaResult<Token> {
+        let token = self.peek().cloned();
+        let token = token.ok_or_else(|| syntax_error(self.offset(), "unexpected end of input"))?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    /// Consume the next token if it is the given (case-insensitive) keyword.
+    fn next_if_keyword(&mut self, keyword: &str) -> bool {
+        let is_keyword = matches!(
+            self.peek(),
+            Some(Token::Identifier(name)) if name.eq_ignore_ascii_case(keyword)
+        );
+        if is_keyword {
+            self.position += 1;
+        }
+        is_keyword
+    }
+
+    fn next_if_symbol(&mut self, symbol: &str) -> bool {
+        let is_symbol = matches!(self.peek(), Some(Token::Symbol(s)) if *s == symbol);
+        if is_symbol {
+            self.position += 1;
+        }
+        is_symbol
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> DeltaResult<()> {
+        let offset = self.offset();
+        match self.next_if_keyword(keyword) {
+            true => Ok(()),
+            false => Err(self.expected(offset, keyword)),
+        }
+    }
+
+    fn expected(&self, offset: usize, what: &str) -> Error {
+        match self.peek() {
+            Some(token) => syntax_error(offset, format!("expected {what}, found {token}")),
+            None => syntax_error(offset, format!("expected {what}, found end of input")),
+        }
+    }
+
+    /// Parse a nested part of the input that starts at `offset`.
+    fn parse_nested(
+        &mut self,
+        offset: usize,
+        parse: impl FnOnce(&mut Self) -> DeltaResult<Expression>,
+    ) -> DeltaResult<Expression> {
+        if self.depth == MAX_DEPTH {
+            return Err(syntax_error(
+                offset,
+                format!("expression nested more than {MAX_DEPTH} deep"),
+            ));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn parse_or(&mut self) -> DeltaResult<Expression> {
+        let mut exprs = vec![self.parse_and()?];
+        while self.next_if_keyword("OR") {
+            exprs.push(self.parse_and()?);
+        }
+        Ok(match exprs.len() {
+            1 => exprs.remove(0),
+            _ => Expression::or_from(exprs),
+        })
+    }
+
+    fn parse_and(&mut self) -> DeltaResult<Expression> {
+        let mut exprs = vec![self.parse_not()?];
+        while self.next_if_keyword("AND") {
+            exprs.push(self.parse_not()?);
+        }
+        Ok(match exprs.len() {
+            1 => exprs.remove(0),
+            _ => Expression::and_from(exprs),
+        })
+    }
+
+    fn parse_not(&mut self) -> DeltaResult<Expression> {
+        let offset = self.offset();
+        match self.next_if_keyword("NOT") {
+            true => Ok(!self.parse_nested(offset, Self::parse_not)?),
+            false => self.parse_comparison(),
+        }
+    }
+
+    fn parse_comparison(&mut self) -> DeltaResult<Expression> {
+        use BinaryOperator::*;
+        let left = self.parse_operand()?;
+        if self.next_if_keyword("IS") {
+            let negated = self.next_if_keyword("NOT");
+            self.expect_keyword("NULL")?;
+            return Ok(match negated {
+                true => !left.is_null(),
+                false => left.is_null(),
+            });
+        }
+        let op = match self.peek() {
+            Some(Token::Symbol("=" | "==")) => Equal,
+            Some(Token::Symbol("!=" | "<>")) => NotEqual,
+            Some(Token::Symbol("<")) => LessThan,
+            Some(Token::Symbol("<=")) => LessThanOrEqual,
+            Some(Token::Symbol(">")) => GreaterThan,
+            Some(Token::Symbol(">=")) => GreaterThanOrEqual,
+            _ => return Ok(left),
+        };
+        self.position += 1;
+        let right = self.parse_operand()?;
+        Ok(match (left, right) {
+            (Expression::Column(name), Expression::Literal(value)) => {
+                Expression::binary(op, self.widen_column(name, &value), value)
+            }
+            (Expression::Literal(value), Expression::Column(name)) => {
+                Expression::binary(op, value.clone(), self.widen_column(name, &value))
+            }
+            (left, right) => Expression::binary(op, left, right),
+        })
+    }
+
+    /// The column, cast to the type of the number `value` compared with it if the value doesn't
+    /// fit in the column's type but every value of that type fits in the value's type.
+    fn widen_column(&self, name: String, value: &Scalar) -> Expression {
+        use PrimitiveType::*;
+        let Some(DataType::Primitive(column_type)) =
+            find_schema_field(self.schema, &name).map(StructField::data_type)
+        else {
+            return Expression::Column(name);
+        };
+        let value_type = value.data_type();
+        let DataType::Primitive(value_primitive) = &value_type else {
+            return Expression::Column(name);
+        };
+        let widens = matches!(
+            (column_type, value_primitive),
+            (Byte, Short | Integer | Long | Float | Double)
+                | (Short, Integer | Long | Float | Double)
+                | (Integer, Long | Double)
+                | (Float, Double)
+        );
+        let fits = value.coerce_to(&DataType::Primitive(column_type.clone()));
+        let column = Expression::Column(name);
+        match widens && fits.is_none() {
+            true => column.cast(value_type),
+            false => column,
+        }
+    }
+
+    fn parse_operand(&mut self) -> DeltaResult<Expression> {
+        let offset = self.offset();
+        match self.next()? {
+            Token::Symbol("(") => {
+                let expression = self.parse_nested(offset, Self::parse_or)?;
+                match self.next_if_symbol(")") {
+                    true => Ok(expression),
+                    false => Err(self.expected(self.offset(), "')'")),
+                }
+            }
+            Token::Symbol("-") => match self.next()? {
+                Token::Number(number) => parse_number(offset, &format!("-{number}")),
+                _ => Err(syntax_error(offset, "expected a number after '-'")),
+            },
+            Token::Number(number) => parse_number(offset, &number),
+            Token::String(value) => Ok(Expression::literal(value)),
+            Token::Identifier(name) if name.eq_ignore_ascii_case("TRUE") => {
+                Ok(Expression::literal(true))
+            }
+            Token::Identifier(name) if name.eq_ignore_ascii_case("FALSE") => {
+                Ok(Expression::literal(false))
+            }
+            // Typed when compared with a column, and a boolean otherwise, like in `NULL AND a > 2`
+            Token::Identifier(name) if name.eq_ignore_ascii_case("NULL") => {
+                Ok(Expression::literal(Scalar::Null(DataType::BOOLEAN)))
+            }
+            Token::Identifier(name) | Token::QuotedIdentifier(name) => {
+                self.parse_column(offset, name)
+            }
+            token => Err(syntax_error(
+                offset,
+                format!("expected a column, a literal or '(', found {token}"),
+            )),
+        }
+    }
+
+    fn parse_column(&mut self, offset: usize, first_step: String) -> DeltaResult<Expression> {
+        let mut name = first_step;
+        while self.next_if_symbol(".") {
+            match self.next()? {
+                Token::Identifier(step) | Token::QuotedIdentifier(step) => {
+                    name.push('.');
+                    name.push_str(&step);
+                }
+                token => {
+                    return Err(syntax_error(
+                        offset,
+                        format!("expected a field name after '.', found {token}"),
+                    ))
+                }
+            }
+        }
+        if find_schema_field(self.schema, &name).is_none() {
+            return Err(syntax_error(offset, format!("no column named {name}")));
+        }
+        Ok(Expression::column(name))
+    }
+}
+
+fn parse_number(offset: usize, number: &str) -> DeltaResult<Expression> {
+    let invalid = || syntax_error(offset, format!("invalid number {number}"));
+    if number.contains('.') {
+        let value: f64 = number.parse().map_err(|_| invalid())?;
+        return Ok(Expression::literal(value));
+    }
+    let value: i64 = number.parse().map_err(|_| invalid())?;
+    Ok(match i32::try_from(value) {
+        Ok(value) => Expression::literal(value),
+        Err(_) => Expression::literal(value),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::schema::{DataType, StructField, StructType};
+
+    fn schema() -> SchemaRef {
+        Arc::new(StructType::new(vec![
+            StructField::new("a", DataType::INTEGER, true),
+            StructField::new("b", DataType::STRING, true),
+            StructField::new("c", DataType::LONG, true),
+            StructField::new(
+                "s",
+                StructType::new(vec![StructField::new("x", DataType::DOUBLE, true)]),
+                true,
+            ),
+            StructField::new("my col", DataType::BOOLEAN, true),
+            StructField::new("f", DataType::FLOAT, true),
+        ]))
+    }
+
+    #[test]
+    fn test_parse() {
+        let a = || Expression::column("a");
+        let b = || Expression::column("b");
+        let cases = [
+            (
+                "a > 2 AND b = 'x'",
+                Expression::and_from([a().gt(Expression::literal(2)), b().eq("x".into())]),
+            ),
+            (
+                "a <= -1 or NOT (b <> 'it''s') and a IS NOT NULL",
+                Expression::or_from([
+                    a().le(Expression::literal(-1)),
+                    Expression::and_from([!b().ne("it's".into()), !a().is_null()]),
+                ]),
+            ),
+            // the literal is converted to the type of the column
+            (
+                "c = 3",
+                Expression::column("c").eq(Expression::literal(3i64)),
+            ),
+            (
+                "s.x >= 1.5 OR `my col` = true",
+                Expression::or_from([
+                    Expression::column("s.x").ge(Expression::literal(1.5)),
+                    Expression::column("my col").eq(Expression::literal(true)),
+                ]),
+            ),
+            ("((b IS NULL))", b().is_null()),
+            // a decimal that is exactly a float is compared as one, otherwise as a double
+            (
+                "f > 1.5",
+                Expression::column("f").gt(Expression::literal(1.5f32)),
+            ),
+            (
+                "f > 0.1",
+                Expression::column("f")
+                    .cast(DataType::DOUBLE)
+                    .gt(Expression::literal(0.1)),
+            ),
+            // an integer too big for the column's type is compared as a LONG
+            (
+                "3000000000 > a",
+                Expression::literal(3000000000i64).gt(a().cast(DataType::LONG)),
+            ),
+            (
+                "a = null OR NULL",
+                Expression::or_from([
+                    a().eq(Expression::literal(Scalar::Null(DataType::INTEGER))),
+                    Expression::literal(Scalar::Null(DataType::BOOLEAN)),
+                ]),
+            ),
+            (
+                &format!("{}a > 1", "NOT ".repeat(MAX_DEPTH)),
+                (0..MAX_DEPTH).fold(a().gt(Expression::literal(1)), |expr, _| !expr),
+            ),
+        ];
+        for (sql, expected) in cases {
+            assert_eq!(
+                Expression::parse(sql, &schema()).unwrap(),
+                expected,
+                "{sql}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let deep_not = format!("{}a > 1", "NOT ".repeat(MAX_DEPTH + 1));
+        let deep_parens = format!("{}a > 1{}", "(".repeat(100), ")".repeat(100));
+        let cases = [
+            ("a >", "Syntax error at position 3: unexpected end of input"),
+            ("a > 2 b", "Syntax error at position 6: unexpected 'b'"),
+            (
+                "(a > 2",
+                "Syntax error at position 6: expected ')', found end of input",
+            ),
+            ("b = 'x", "Syntax error at position 4: unterminated '"),
+            (
+                "a ! 2",
+                "Syntax error at position 2: unexpected character '!'",
+            ),
+            ("d = 1", "Syntax error at position 0: no column named d"),
+            (
+                "a IS 1",
+                "Syntax error at position 5: expected NULL, found '1'",
+            ),
+            (
+                &deep_not,
+                "Syntax error at position 256: expression nested more than 64 deep",
+            ),
+            (
+                &deep_parens,
+                "Syntax error at position 64: expression nested more than 64 deep",
+            ),
+        ];
+        for (sql, expected) in cases {
+            let error = Expression::parse(sql, &schema()).unwrap_err().to_string();
+            assert_eq!(
+                error,
+                format!("Invalid expression evaluation: {expected}"),
+                "{sql}"
+            );
+        }
+    }
+}
@@ -151,6 +151,50 @@ impl Scalar {
     pub fn is_null(&self) -> bool {
         matches!(self, Self::Null(_))
     }
+
+    /// Convert this scalar to the given data type, if that is possible without losing any
+    /// information: an integer can be converted to any integer, float or decimal type that can
+    /// represent it exactly, a float to a double, and a double to a float if it is exactly a float
+    /// (like `1.5`, but not `0.1`). A null converts to a null of any type.
+    pub(crate) fn coerce_to(&self, data_type: &DataType) -> Option<Scalar> {
+        use PrimitiveType::*;
+        if self.data_type() == *data_type {
+            return Some(self.clone());
+        }
+        let DataType::Primitive(primitive) = data_type else {
+            return self.is_null().then(|| Self::Null(data_type.clone()));
+        };
+        let int = match self {
+            Self::Null(_) => return Some(Self::Null(data_type.clone())),
+            Self::Float(value) if *primitive == Double => return Some(Self::Double(*value as f64)),
+            Self::Double(value) if *primitive == Float => {
+                let float = *value as f32;
+                return (float as f64 == *value).then_some(Self::Float(float));
+            }
+            Self::Byte(value) => *value as i64,
+            Self::Short(value) => *value as i64,
+            Self::Integer(value) => *value as i64,
+            Self::Long(value) => *value,
+            _ => return None,
+        };
+        // the largest integers that floats and doubles can represent exactly, and all below
+        const MAX_EXACT_FLOAT: u64 = 1 << f32::MANTISSA_DIGITS;
+        const MAX_EXACT_DOUBLE: u64 = 1 << f64::MANTISSA_DIGITS;
+        match primitive {
+            Byte => i8::try_from(int).ok().map(Self::Byte),
+            Short => i16::try_from(int).ok().map(Self::Short),
+            Integer => i32::try_from(int).ok().map(Self::Integer),
+            Long => Some(Self::Long(int)),
+            Float => (int.unsigned_abs() <= MAX_EXACT_FLOAT).then_some(Self::Float(int as f32)),
+            Double => (int.unsigned_abs() <= MAX_EXACT_DOUBLE).then_some(Self::Double(int as f64)),
+            Decimal(precision, scale) => {
+                let value = (int as i128).checked_mul(10_i128.checked_pow(*scale as u32)?)?;
+                let max = 10_i128.checked_pow(*precision as u32)?;
+                (value.abs() < max).then_some(Self::Decimal(value, *precision, *scale))
+            }
+            _ => None,
+        }
+    }
 }
 
 impl Display for Scalar {
@@ -520,4 +564,50 @@ mod tests {
         assert_eq!(&format!("{}", column_op), "3.1415927 IN Column(item)");
         assert_eq!(&format!("{}", column_not_op), "'Cool' NOT IN Column(item)");
     }
+
+    #[test]
+    fn test_coerce_to() {
+        let cases = [
+            (Scalar::Integer(5), DataType::LONG, Some(Scalar::Long(5))),
+            (Scalar::Long(300), DataType::BYTE, None),
+            (Scalar::Long(-7), DataType::SHORT, Some(Scalar::Short(-7))),
+            (
+                Scalar::Long(1 << 24),
+                DataType::FLOAT,
+                Some(Scalar::Float(16777216.0)),
+            ),
+            (Scalar::Long((1 << 24) + 1), DataType::FLOAT, None),
+            (Scalar::Long(i64::MIN), DataType::FLOAT, None),
+            (Scalar::Long(i64::MIN), DataType::DOUBLE, None),
+            (
+                Scalar::Float(1.5),
+                DataType::DOUBLE,
+                Some(Scalar::Double(1.5)),
+            ),
+            (
+                Scalar::Double(1.5),
+                DataType::FLOAT,
+                Some(Scalar::Float(1.5)),
+            ),
+            (Scalar::Double(0.1), DataType::FLOAT, None),
+            (
+                Scalar::Integer(-12),
+                DataType::decimal(5, 2).unwrap(),
+                Some(Scalar::Decimal(-1200, 5, 2)),
+            ),
+            (
+                Scalar::Integer(1000),
+                DataType::decimal(5, 2).unwrap(),
+                None,
+            ),
+            (Scalar::from("1"), DataType::INTEGER, None),
+        ];
+        for (scalar, data_type, expected) in cases {
+            assert_eq!(
+                scalar.coerce_to(&data_type),
+                expected,
+                "{scalar} to {data_type}"
+            );
+        }
+    }
 }
@@ -112,6 +112,10 @@ pub struct FileMeta {
 /// It contains one Expression which can be evaluated on multiple ColumnarBatches.
 /// Connectors can implement this trait to optimize the evaluation using the
 /// connector specific capabilities.
+///
+/// Evaluators must be `Send + Sync`, so an evaluator can be created once and then shared by many
+/// threads evaluating different batches concurrently. Evaluating takes `&self`, but an evaluator
+/// may still keep internal caches across batches, as long as they are synchronized.
 pub trait ExpressionEvaluator: Send + Sync {
     /// Evaluate the expression on a given EngineData.
     ///
@@ -510,6 +510,15 @@ impl DataType {
     pub fn array_type(elements: ArrayType) -> Self {
         DataType::Array(Box::new(elements))
     }
+
+    /// Returns true if this is an integer, floating point or decimal type.
+    pub fn is_numeric(&self) -> bool {
+        use PrimitiveType::*;
+        matches!(
+            self,
+            DataType::Primitive(Byte | Short | Integer | Long | Float | Double | Decimal(..))
+        )
+    }
 }
 
 impl Display for DataType {